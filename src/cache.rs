@@ -0,0 +1,60 @@
+// src/cache.rs
+//
+// Small TTL cache for read-mostly UI state (the order screen's menu) that
+// should pick up admin-side edits without a manual reload, while still
+// avoiding a DB round-trip on every frame.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+/// A cached value plus when it was fetched and how long it stays fresh.
+/// `Default` starts empty, so the first `get_or_fetch`/`fetch_mut` call
+/// always (re)populates it.
+pub struct Cached<T> {
+    value: Option<T>,
+    fetched_at: Option<Instant>,
+    ttl: Duration,
+}
+
+// Hand-written instead of `#[derive(Default)]`: the derive would require
+// `T: Default`, but an empty `Cached<T>` never actually needs one — `value`
+// starts `None` regardless of `T`.
+impl<T> Default for Cached<T> {
+    fn default() -> Self {
+        Self { value: None, fetched_at: None, ttl: Duration::ZERO }
+    }
+}
+
+impl<T> Cached<T> {
+    fn is_stale(&self) -> bool {
+        match (&self.value, self.fetched_at) {
+            (Some(_), Some(at)) => at.elapsed() >= self.ttl,
+            _ => true,
+        }
+    }
+
+    /// Returns the cached value, re-running `fetch` first if the entry is
+    /// absent or `ttl` has elapsed since the last fetch.
+    pub fn get_or_fetch(&mut self, ttl: Duration, fetch: impl FnOnce() -> Result<T>) -> Result<&T> {
+        self.fetch_mut(ttl, fetch).map(|v| &*v)
+    }
+
+    /// Like `get_or_fetch`, but hands back a mutable reference so the caller
+    /// can tweak the cached value in place (e.g. change the active filter)
+    /// without forcing a fresh fetch on the next frame.
+    pub fn fetch_mut(&mut self, ttl: Duration, fetch: impl FnOnce() -> Result<T>) -> Result<&mut T> {
+        self.ttl = ttl;
+        if self.is_stale() {
+            self.value = Some(fetch()?);
+            self.fetched_at = Some(Instant::now());
+        }
+        Ok(self.value.as_mut().expect("just populated above"))
+    }
+
+    /// Forces the next `get_or_fetch`/`fetch_mut` call to re-fetch, e.g.
+    /// after a watcher reports the underlying collection changed.
+    pub fn invalidate(&mut self) {
+        self.fetched_at = None;
+    }
+}
@@ -0,0 +1,109 @@
+// src/cli.rs
+//
+// Headless CLI entry points for scheduled backups (cron/CI): `--export` and
+// `--import`, each paired with `--password-file`, run the corresponding
+// `services::backup` operation without opening the egui window.
+
+use anyhow::{bail, Context, Result};
+
+use crate::{config, db, services::backup};
+
+enum Command {
+    Export { path: String, password_file: String },
+    Import { path: String, password_file: String },
+}
+
+fn parse_args(args: &[String]) -> Result<Option<Command>> {
+    let mut export_path = None;
+    let mut import_path = None;
+    let mut password_file = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--export" => {
+                i += 1;
+                export_path = Some(args.get(i).context("--export requires a path")?.clone());
+            }
+            "--import" => {
+                i += 1;
+                import_path = Some(args.get(i).context("--import requires a path")?.clone());
+            }
+            "--password-file" => {
+                i += 1;
+                password_file = Some(args.get(i).context("--password-file requires a path")?.clone());
+            }
+            other => bail!("unknown argument: {other}"),
+        }
+        i += 1;
+    }
+
+    match (export_path, import_path) {
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => bail!("--export and --import are mutually exclusive"),
+        (Some(path), None) => {
+            let password_file = password_file.context("--export requires --password-file")?;
+            Ok(Some(Command::Export { path, password_file }))
+        }
+        (None, Some(path)) => {
+            let password_file = password_file.context("--import requires --password-file")?;
+            Ok(Some(Command::Import { path, password_file }))
+        }
+    }
+}
+
+async fn run(command: Command) -> Result<String> {
+    let profile = config::active()
+        .context("load config")?
+        .map(|(_, p)| p)
+        .context("no saved MongoDB URI; connect once via the GUI first")?;
+    let uri = profile
+        .mongo_uri
+        .context("active profile has no direct MongoDB URI (agent-resolved profiles aren't supported headlessly)")?;
+    // Headless export/import never reacts to live changes, so always use
+    // the no-op change-stream bus regardless of the saved transport.
+    let db = db::connect(&uri, config::EventTransport::ChangeStream, None, "bestelldesk-cli")
+        .await
+        .context("connect to MongoDB")?;
+
+    match command {
+        Command::Export { path, password_file } => {
+            let password = std::fs::read_to_string(&password_file).context("read password file")?;
+            backup::export_to_file(&db, &path, password.trim()).await?;
+            Ok(format!("exported to {path}"))
+        }
+        Command::Import { path, password_file } => {
+            let password = std::fs::read_to_string(&password_file).context("read password file")?;
+            let snapshot_path = backup::import_from_file(&db, &path, password.trim()).await?;
+            Ok(format!("imported from {path} (safety snapshot: {snapshot_path})"))
+        }
+    }
+}
+
+/// Runs `--export <path> --password-file <path>` or `--import <path>
+/// --password-file <path>` if present in `args`, connecting to the MongoDB
+/// URI saved in `LocalConfig`, and prints an `ok`/error line to stdout or
+/// stderr. Returns `Some(exit_code)` when a headless command was recognized
+/// and handled — the caller should exit with that code instead of starting
+/// the GUI — or `None` if no CLI flags were given.
+pub fn try_run_headless(args: &[String], rt: &tokio::runtime::Runtime) -> Option<i32> {
+    let command = match parse_args(args) {
+        Ok(Some(c)) => c,
+        Ok(None) => return None,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            return Some(2);
+        }
+    };
+
+    match rt.block_on(run(command)) {
+        Ok(msg) => {
+            println!("ok: {msg}");
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            Some(1)
+        }
+    }
+}
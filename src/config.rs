@@ -2,11 +2,86 @@ use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::{fs, path::PathBuf};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Nightly,
+}
+
+impl UpdateChannel {
+    pub const ALL: [UpdateChannel; 2] = [UpdateChannel::Stable, UpdateChannel::Nightly];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "Stable",
+            UpdateChannel::Nightly => "Nightly",
+        }
+    }
+}
+
+/// Selects which transport feeds the live `AppMsg` watcher channel.
+/// `ChangeStream` needs a MongoDB replica set; `Mqtt` works against plain
+/// standalone/shared clusters reachable only through the agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTransport {
+    #[default]
+    ChangeStream,
+    Mqtt,
+}
+
+impl EventTransport {
+    pub const ALL: [EventTransport; 2] = [EventTransport::ChangeStream, EventTransport::Mqtt];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventTransport::ChangeStream => "MongoDB change streams",
+            EventTransport::Mqtt => "MQTT",
+        }
+    }
+}
+
+/// A named connection target (e.g. "dev"/"prod"), each carrying its own
+/// MongoDB URI or agent endpoint — lets users switch servers from the
+/// connection UI instead of hand-editing `config.json`.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct LocalConfig {
+pub struct ServerProfile {
+    /// Display name shown in the profile dropdown; falls back to the map key.
+    pub label: Option<String>,
     pub mongo_uri: Option<String>,
+    pub agent_host: Option<String>,
+    #[serde(default)]
     pub remember_server: bool,
-    pub client_id: Option<String>, 
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LocalConfig {
+    #[serde(default)]
+    pub profiles: std::collections::BTreeMap<String, ServerProfile>,
+    pub active_profile: Option<String>,
+
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Unix timestamp (seconds) of the last startup update check, so a quick
+    /// relaunch doesn't block on the network every time.
+    pub last_update_check: Option<i64>,
+    #[serde(default)]
+    pub event_transport: EventTransport,
+    /// Broker URL (e.g. "mqtt://host:1883"), required when `event_transport` is `Mqtt`.
+    pub mqtt_broker_url: Option<String>,
+
+    /// Legacy single-server fields from before named profiles existed. Only
+    /// ever read, never written back; `load()` migrates them into a
+    /// `default` profile the first time an old config.json is opened.
+    #[serde(default, skip_serializing)]
+    mongo_uri: Option<String>,
+    #[serde(default, skip_serializing)]
+    remember_server: bool,
+    #[serde(default, skip_serializing)]
+    agent_host: Option<String>,
 }
 
 fn config_path() -> anyhow::Result<PathBuf> {
@@ -23,7 +98,22 @@ pub fn load() -> anyhow::Result<LocalConfig> {   // <-- pub
         return Ok(LocalConfig::default());
     }
     let bytes = fs::read(p)?;
-    Ok(serde_json::from_slice(&bytes)?)
+    let mut cfg: LocalConfig = serde_json::from_slice(&bytes)?;
+
+    if cfg.profiles.is_empty() && (cfg.mongo_uri.is_some() || cfg.agent_host.is_some()) {
+        cfg.profiles.insert(
+            "default".to_string(),
+            ServerProfile {
+                label: None,
+                mongo_uri: cfg.mongo_uri.take(),
+                agent_host: cfg.agent_host.take(),
+                remember_server: cfg.remember_server,
+            },
+        );
+        cfg.active_profile = Some("default".to_string());
+    }
+
+    Ok(cfg)
 }
 
 pub fn save(cfg: &LocalConfig) -> anyhow::Result<()> {   // <-- pub
@@ -32,3 +122,26 @@ pub fn save(cfg: &LocalConfig) -> anyhow::Result<()> {   // <-- pub
     fs::write(p, bytes)?;
     Ok(())
 }
+
+/// Every named profile, sorted by name.
+pub fn profiles() -> anyhow::Result<Vec<(String, ServerProfile)>> {
+    let cfg = load()?;
+    Ok(cfg.profiles.into_iter().collect())
+}
+
+/// The active profile (name + settings), if one is selected.
+pub fn active() -> anyhow::Result<Option<(String, ServerProfile)>> {
+    let cfg = load()?;
+    Ok(cfg
+        .active_profile
+        .clone()
+        .and_then(|name| cfg.profiles.get(&name).cloned().map(|p| (name, p))))
+}
+
+/// Switches the active profile, without changing any profile's settings.
+pub fn set_active(name: &str) -> anyhow::Result<()> {
+    let mut cfg = load()?;
+    anyhow::ensure!(cfg.profiles.contains_key(name), "no profile named {name:?}");
+    cfg.active_profile = Some(name.to_string());
+    save(&cfg)
+}
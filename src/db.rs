@@ -1,16 +1,33 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
+use mongodb::change_stream::event::ResumeToken;
 use mongodb::{options::ClientOptions, Client, Database};
 use mongodb::bson::doc;
 use mongodb::Collection;
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::config::EventTransport;
+use crate::services::events::{self, EventBus, Topic};
+use crate::services::migrations;
+
+const WATCH_BACKOFF_BASE: Duration = Duration::from_millis(500);
+const WATCH_BACKOFF_CAP: Duration = Duration::from_secs(60);
+
 #[derive(Clone)]
 pub struct Db {
     pub client: Client,
     pub db: Database,
+    pub bus: Arc<dyn EventBus>,
 }
 
-pub async fn connect(uri: &str) -> Result<Db> {
+pub async fn connect(
+    uri: &str,
+    transport: EventTransport,
+    broker_url: Option<&str>,
+    client_id: &str,
+) -> Result<Db> {
     let mut opts = ClientOptions::parse(uri).await?;
     if opts.app_name.is_none() {
         opts.app_name = Some("BestellDesk".into());
@@ -20,7 +37,10 @@ pub async fn connect(uri: &str) -> Result<Db> {
         .default_database()
         .unwrap_or_else(|| client.database("bestelldesk"));
     db.run_command(doc! { "ping": 1 }).await?;
-    Ok(Db { client, db })
+    let bus = events::build(transport, broker_url, client_id, db.clone())?;
+    let dbh = Db { client, db, bus };
+    migrations::run(&dbh).await?;
+    Ok(dbh)
 }
 
 impl Db {
@@ -28,60 +48,82 @@ impl Db {
     pub fn collection<T: Send + Sync>(&self, name: &str) -> Collection<T> {
         self.db.collection::<T>(name)
     }
-}
 
-// Watcher für Settings
-pub async fn watch_settings(db: Db, tx: UnboundedSender<crate::AppMsg>) {
-    let coll = db.collection::<crate::model::AppSettings>("settings");
-    let mut stream = match coll.watch().await {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    while let Some(_ev) =
-        futures_util::TryStreamExt::try_next(&mut stream).await.ok().flatten()
-    {
-        let _ = tx.send(crate::AppMsg::SettingsChanged);
+    /// Announces a collection change through the configured transport (a
+    /// no-op when change streams are already covering it).
+    pub async fn notify(&self, topic: Topic) {
+        self.bus.publish(topic).await;
     }
 }
 
-// Watcher für Suppliers
-pub async fn watch_suppliers(db: Db, tx: UnboundedSender<crate::AppMsg>) {
-    let coll = db.collection::<crate::model::Supplier>("suppliers");
-    let mut stream = match coll.watch().await {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    while let Some(_ev) =
-        futures_util::TryStreamExt::try_next(&mut stream).await.ok().flatten()
-    {
-        let _ = tx.send(crate::AppMsg::SuppliersChanged);
+/// Watches `coll_name`, sending `msg_fn()` into `tx` on every change event.
+/// Reconnects on any watch/stream error with exponential backoff (capped,
+/// reset after a successful event), resuming from the last seen
+/// `resume_token` so events produced during a disconnect aren't lost.
+async fn watch_collection<T, F>(
+    database: Database,
+    coll_name: &str,
+    msg_fn: F,
+    tx: UnboundedSender<crate::AppMsg>,
+) where
+    T: Send + Sync + serde::de::DeserializeOwned + Unpin,
+    F: Fn() -> crate::AppMsg,
+{
+    let coll = database.collection::<T>(coll_name);
+    let mut resume_token: Option<ResumeToken> = None;
+    let mut delay = WATCH_BACKOFF_BASE;
+
+    loop {
+        let mut stream = match coll.watch().resume_after(resume_token.clone()).await {
+            Ok(s) => s,
+            Err(_) => {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(WATCH_BACKOFF_CAP);
+                continue;
+            }
+        };
+
+        loop {
+            match futures_util::TryStreamExt::try_next(&mut stream).await {
+                Ok(Some(_ev)) => {
+                    resume_token = stream.resume_token();
+                    let _ = tx.send(msg_fn());
+                    delay = WATCH_BACKOFF_BASE;
+                }
+                Ok(None) => break,
+                Err(_) => break,
+            }
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(WATCH_BACKOFF_CAP);
     }
 }
 
-// Watcher für Dishes
-pub async fn watch_dishes(db: Db, tx: UnboundedSender<crate::AppMsg>) {
-    let coll = db.collection::<crate::model::Dish>("dishes");
-    let mut stream = match coll.watch().await {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    while let Some(_ev) =
-        futures_util::TryStreamExt::try_next(&mut stream).await.ok().flatten()
-    {
-        let _ = tx.send(crate::AppMsg::DishesChanged);
-    }
+pub async fn watch_settings(db: Database, tx: UnboundedSender<crate::AppMsg>) {
+    watch_collection::<crate::model::AppSettings, _>(
+        db,
+        "settings",
+        || crate::AppMsg::SettingsChanged,
+        tx,
+    )
+    .await;
 }
 
-// Watcher für Orders
-pub async fn watch_orders(db: Db, tx: UnboundedSender<crate::AppMsg>) {
-    let coll = db.collection::<crate::model::Order>("orders");
-    let mut stream = match coll.watch().await {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    while let Some(_ev) =
-        futures_util::TryStreamExt::try_next(&mut stream).await.ok().flatten()
-    {
-        let _ = tx.send(crate::AppMsg::OrdersChanged);
-    }
+pub async fn watch_suppliers(db: Database, tx: UnboundedSender<crate::AppMsg>) {
+    watch_collection::<crate::model::Supplier, _>(
+        db,
+        "suppliers",
+        || crate::AppMsg::SuppliersChanged,
+        tx,
+    )
+    .await;
+}
+
+pub async fn watch_dishes(db: Database, tx: UnboundedSender<crate::AppMsg>) {
+    watch_collection::<crate::model::Dish, _>(db, "dishes", || crate::AppMsg::DishesChanged, tx).await;
+}
+
+pub async fn watch_orders(db: Database, tx: UnboundedSender<crate::AppMsg>) {
+    watch_collection::<crate::model::Order, _>(db, "orders", || crate::AppMsg::OrdersChanged, tx).await;
 }
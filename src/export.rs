@@ -0,0 +1,137 @@
+// src/export.rs
+//
+// Printable, self-contained HTML menu/price-list export for a supplier —
+// mirrors the admin's own `row_label`/`eur` formatting so the printout
+// matches what's shown in `page_dishes`.
+
+use anyhow::{Context, Result};
+use mongodb::bson::oid::ObjectId;
+
+use crate::db::Db;
+use crate::model::Dish;
+use crate::services::{categories, dishes, suppliers};
+
+fn eur(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.abs();
+    format!("{sign}€{}.{}", abs / 100, format!("{:02}", abs % 100))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn dish_row_html(d: &Dish) -> String {
+    let nr = d.number.clone().unwrap_or_default();
+    let name = if nr.is_empty() {
+        escape_html(&d.name)
+    } else {
+        format!("{}: {}", escape_html(&nr), escape_html(&d.name))
+    };
+
+    if let Some(groups) = d.variant_groups.as_ref().filter(|g| !g.is_empty()) {
+        let tables = groups
+            .iter()
+            .map(|g| {
+                let rows = g
+                    .options
+                    .iter()
+                    .map(|o| format!("<tr><td>{}</td><td>{}</td></tr>", escape_html(&o.label), eur(o.price_cents)))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!(
+                    "<div class=\"variant-group\"><em>{}</em><table>{}</table></div>",
+                    escape_html(&g.name),
+                    rows
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("<li class=\"dish\"><div class=\"dish-name\">{name}</div>{tables}</li>")
+    } else if let Some(sizes) = d.pizza_sizes.as_ref().filter(|s| !s.is_empty()) {
+        let rows = sizes
+            .iter()
+            .map(|s| format!("<tr><td>{}</td><td>{}</td></tr>", escape_html(&s.label), eur(s.price_cents)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("<li class=\"dish\"><div class=\"dish-name\">{name}</div><table>{rows}</table></li>")
+    } else {
+        format!(
+            "<li class=\"dish\"><div class=\"dish-name\">{name}</div><div class=\"dish-price\">{}</div></li>",
+            eur(d.price_cents)
+        )
+    }
+}
+
+/// Renders the given supplier's menu as a self-contained, printable HTML
+/// document: a header (name, delivery fee, generation timestamp) followed by
+/// dishes grouped under their categories in rank order.
+pub async fn menu_html(db: &Db, supplier_id: ObjectId) -> Result<String> {
+    let supplier = suppliers::get_supplier(db, supplier_id)
+        .await?
+        .context("supplier not found")?;
+
+    let cats = categories::list_by_supplier(db, supplier_id).await?;
+    let all_dishes = dishes::list_by_supplier(db, supplier_id).await?;
+
+    let generated_at = mongodb::bson::DateTime::now().to_string();
+
+    let mut sections = String::new();
+    for c in &cats {
+        let Some(cid) = c.id else { continue };
+        let mut items: Vec<&Dish> = all_dishes.iter().filter(|d| d.categories.iter().any(|x| *x == cid)).collect();
+        items.sort_by_key(|d| d.number.clone().unwrap_or_default());
+        if items.is_empty() {
+            continue;
+        }
+        let rows = items.iter().map(|d| dish_row_html(d)).collect::<Vec<_>>().join("\n");
+        sections.push_str(&format!(
+            "<section><h2>{}</h2><ul class=\"dishes\">{}</ul></section>\n",
+            escape_html(&c.name),
+            rows
+        ));
+    }
+
+    // Dishes not assigned to any category, listed last.
+    let uncategorized: Vec<&Dish> = all_dishes.iter().filter(|d| d.categories.is_empty()).collect();
+    if !uncategorized.is_empty() {
+        let rows = uncategorized.iter().map(|d| dish_row_html(d)).collect::<Vec<_>>().join("\n");
+        sections.push_str(&format!("<section><h2>Sonstiges</h2><ul class=\"dishes\">{rows}</ul></section>\n"));
+    }
+
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+<meta charset="utf-8">
+<title>{name} — Speisekarte</title>
+<style>
+  body {{ font-family: sans-serif; max-width: 700px; margin: 2em auto; color: #222; }}
+  header {{ border-bottom: 2px solid #333; margin-bottom: 1.5em; padding-bottom: 0.5em; }}
+  h1 {{ margin-bottom: 0.2em; }}
+  .meta {{ color: #666; font-size: 0.9em; }}
+  section {{ margin-bottom: 1.5em; }}
+  ul.dishes {{ list-style: none; padding: 0; }}
+  li.dish {{ display: flex; justify-content: space-between; align-items: baseline; padding: 0.3em 0; border-bottom: 1px dotted #ccc; }}
+  .dish-name {{ font-weight: 500; }}
+  .variant-group table, li.dish > table {{ border-collapse: collapse; font-size: 0.9em; }}
+  .variant-group table td, li.dish > table td {{ padding: 0.1em 0.6em 0.1em 0; }}
+  @media print {{ body {{ margin: 0; }} }}
+</style>
+</head>
+<body>
+<header>
+  <h1>{name}</h1>
+  <div class="meta">Liefergebühr: {fee} &middot; Erstellt: {generated_at}</div>
+</header>
+{sections}
+</body>
+</html>
+"#,
+        name = escape_html(&supplier.name),
+        fee = eur(supplier.delivery_fee_cents),
+    ))
+}
@@ -14,24 +14,96 @@ pub struct AppSettings {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub active_supplier_id: Option<ObjectId>,
+
+    /// Optional S3-compatible object storage target for remote backups.
+    #[serde(default)]
+    pub s3_backup: Option<S3BackupConfig>,
+
+    /// Name of the active UI theme preset (see `ui::theme::Theme`).
+    #[serde(default)]
+    pub theme_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3BackupConfig {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// Permission level for an admin account. `Viewer` can only read; `Manager`
+/// may create/update/delete dishes, suppliers and categories; `Owner` can do
+/// the same plus manage other users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Owner,
+    Manager,
+    Viewer,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Owner, Role::Manager, Role::Viewer];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::Owner => "Owner",
+            Role::Manager => "Manager",
+            Role::Viewer => "Viewer",
+        }
+    }
+
+    /// Viewers may only read; Managers and Owners may mutate dishes/suppliers/categories.
+    pub fn can_edit(&self) -> bool {
+        !matches!(self, Role::Viewer)
+    }
 }
 
-/// Admin-Benutzer für das Admin-Panel
+/// Admin-Benutzer für das Admin-Panel, mit Rolle für Berechtigungsprüfungen.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AdminUser {
+pub struct User {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub username: String,
     pub password_hash: String,
+    pub role: Role,
 }
 
 /// Preisvariante für Pizzen
+///
+/// Kept for backward compatibility with dishes created before the generic
+/// variant-group system below; new dishes should use `VariantGroup` instead.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PizzaSize {
     pub label: String,     // z. B. "26cm", "32cm", "Familie"
     pub price_cents: i64,
 }
 
+/// One selectable option within a `VariantGroup`, e.g. "32cm" for a "Size" group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantOption {
+    pub label: String,
+    pub price_cents: i64,
+}
+
+/// A named group of mutually exclusive options a dish can be ordered in,
+/// e.g. "Size" (26cm/32cm/Familie) or "Crust" (thin/thick). Generalizes the
+/// old pizza-only `pizza_sizes` field to any dish.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantGroup {
+    pub name: String,
+    pub options: Vec<VariantOption>,
+}
+
+/// An optional add-on with its own surcharge, e.g. a topping or side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Extra {
+    pub label: String,
+    pub price_cents: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dish {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
@@ -52,9 +124,22 @@ pub struct Dish {
     #[serde(default)]
     pub number: Option<String>,
 
-    /// Nur bei Pizza: Varianten mit Größe → Preis
+    /// Legacy: nur bei Pizza: Varianten mit Größe → Preis. Neue Gerichte
+    /// sollten stattdessen `variant_groups` verwenden.
     #[serde(default)]
     pub pizza_sizes: Option<Vec<PizzaSize>>,
+
+    /// Named variant groups (e.g. "Size", "Crust"), each with labeled options.
+    #[serde(default)]
+    pub variant_groups: Option<Vec<VariantGroup>>,
+
+    /// Optional add-ons with their own surcharge (e.g. toppings).
+    #[serde(default)]
+    pub extras: Option<Vec<Extra>>,
+
+    /// Category ids this dish is listed under.
+    #[serde(default)]
+    pub categories: Vec<ObjectId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,6 +150,9 @@ pub struct DishInput {
     pub tags: Vec<String>,
     pub number: Option<String>,
     pub pizza_sizes: Option<Vec<PizzaSize>>,
+    pub variant_groups: Option<Vec<VariantGroup>>,
+    pub extras: Option<Vec<Extra>>,
+    pub categories: Option<Vec<ObjectId>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,3 +188,64 @@ pub struct Order {
     pub status: String,
     pub created_at: mongodb::bson::DateTime,
 }
+
+/// One menu category under a supplier, ordered by a fractional `rank` key
+/// (see `services::categories`) so moving a category to an arbitrary index
+/// only ever touches that one document instead of renumbering the list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub supplier_id: ObjectId,
+    pub name: String,
+    pub rank: String,
+}
+
+/// A remembered orderer, keyed by the guest-style `client_id` of the device
+/// that placed their orders — lets the order screen prefill the name/note
+/// fields instead of making every repeat order retype them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Customer {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub client_id: String,
+    pub display_name: String,
+    /// Contact/room/desk note, or a recurring per-item note like "no onions".
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// One printed line on an `Invoice`, derived from an `OrderItem` (or
+/// aggregated across several matching ones for a supplier run).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceLine {
+    pub name: String,
+    pub variant: Option<String>,
+    pub qty: i32,
+    pub unit_price_cents: i64,
+    pub line_total_cents: i64,
+}
+
+/// Per-customer sub-total shown on a supplier-run `Invoice` so a treasurer
+/// can see who owes what within the aggregated delivery round.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceCustomerTotal {
+    pub customer_name: String,
+    pub order_code: String,
+    pub subtotal_cents: i64,
+}
+
+/// A receipt derived from one or more `Order` documents. Never persisted to
+/// Mongo — built on demand by `services::invoices` and handed straight to a
+/// renderer, the same way `export.rs` builds a menu document on demand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invoice {
+    pub invoice_number: String,
+    pub issue_date: mongodb::bson::DateTime,
+    pub supplier_name: String,
+    pub lines: Vec<InvoiceLine>,
+    pub items_total_cents: i64,
+    pub delivery_fee_cents: i64,
+    pub grand_total_cents: i64,
+    pub customer_totals: Vec<InvoiceCustomerTotal>,
+}
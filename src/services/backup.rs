@@ -3,10 +3,14 @@ use aes_gcm::{Aes256Gcm, KeyInit, aead::{Aead, Key, generic_array::GenericArray}
 use argon2::{Argon2, Algorithm, Params, Version};
 use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
 use futures_util::TryStreamExt;
-use mongodb::bson::{doc, DateTime, Document};
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use sha2::{Digest, Sha256};
 
 use crate::db::Db;
+use crate::model::Supplier;
+use crate::services::{orders, suppliers};
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /* ---------- Datenstrukturen ---------- */
 
@@ -23,10 +27,22 @@ struct BackupData {
     collections: BTreeMap<String, Vec<Document>>,
 }
 
+/// Encrypted backup index for the chunked/incremental format: the chunk
+/// digests replace the inline `collections` payload, which now lives as
+/// content-addressed files under `chunks/`.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ChunkIndex {
+    meta: Meta,
+    /// Ordered SHA-256 digests (hex); concatenating the decrypted contents of
+    /// the referenced chunk files in this order reproduces the serialized
+    /// `BackupData` JSON.
+    chunks: Vec<String>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct EncBlob {
     version: u32,
-    kdf: String,    // "argon2id"
+    kdf: String,    // "argon2id" or "keyfile"
     m_cost: u32,
     t_cost: u32,
     p_cost: u32,
@@ -34,6 +50,10 @@ struct EncBlob {
     cipher: String, // "aes-256-gcm"
     nonce_b64: String,
     ct_b64: String,
+    /// Present when encrypted under a keyfile master key; lets `import_*`
+    /// fail fast with "key mismatch" instead of an opaque AES-GCM error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_fingerprint: Option<String>,
 }
 
 /* ---------- Hilfsfunktionen ---------- */
@@ -49,9 +69,10 @@ async fn dump_collection(db: &Db, name: &str) -> Result<Vec<Document>> {
 }
 
 fn encrypt(password: &str, plaintext: &[u8]) -> Result<EncBlob> {
-    // Argon2id Key-Derivation
-    let m_cost = 19_456; // KiB
-    let t_cost = 2;
+    // Argon2id Key-Derivation: 64 MiB / 3 Iterationen, spürbar langsamer als
+    // die OWASP-Minimalwerte, aber bei einem lokalen Backup-Tool unkritisch.
+    let m_cost = 65_536; // KiB
+    let t_cost = 3;
     let p_cost = 1;
 
     let params = Params::new(m_cost, t_cost, p_cost, Some(32))
@@ -86,6 +107,7 @@ fn encrypt(password: &str, plaintext: &[u8]) -> Result<EncBlob> {
         cipher: "aes-256-gcm".into(),
         nonce_b64: base64::engine::general_purpose::STANDARD.encode(&nonce),
         ct_b64: base64::engine::general_purpose::STANDARD.encode(&ct),
+        key_fingerprint: None,
     })
 }
 
@@ -119,37 +141,267 @@ fn decrypt(password: &str, enc: &EncBlob) -> Result<Vec<u8>> {
 
     let pt = cipher
         .decrypt(GenericArray::from_slice(&nonce), ct.as_ref())
-        .map_err(|_e| anyhow::anyhow!("aes-gcm decrypt failed"))?;
+        .map_err(|_e| anyhow::anyhow!("wrong password or corrupted file"))?;
 
     Ok(pt)
 }
 
-/* ---------- Public API ---------- */
+fn encrypt_with_key(key_bytes: &[u8; 32], fingerprint: &str, plaintext: &[u8]) -> Result<EncBlob> {
+    let key = Key::<Aes256Gcm>::from_slice(key_bytes);
+    let cipher = Aes256Gcm::new(key);
 
-pub async fn export_to_file(db: &Db, path: &str, password: &str) -> Result<()> {
-    // Relevante Collections
-    let names = [
-        "settings",
-        "suppliers",
-        "categories",
-        "dishes",
-        "orders",
-        "admin_users",
-    ];
+    let mut nonce = [0u8; 12];
+    getrandom::fill(&mut nonce).map_err(|e| anyhow::anyhow!("getrandom nonce: {e}"))?;
+
+    let ct = cipher
+        .encrypt(GenericArray::from_slice(&nonce), plaintext)
+        .map_err(|_e| anyhow::anyhow!("aes-gcm encrypt failed"))?;
+
+    Ok(EncBlob {
+        version: 1,
+        kdf: "keyfile".into(),
+        m_cost: 0,
+        t_cost: 0,
+        p_cost: 0,
+        salt_b64: String::new(),
+        cipher: "aes-256-gcm".into(),
+        nonce_b64: B64.encode(nonce),
+        ct_b64: B64.encode(&ct),
+        key_fingerprint: Some(fingerprint.to_string()),
+    })
+}
+
+fn decrypt_with_key(key_bytes: &[u8; 32], fingerprint: &str, enc: &EncBlob) -> Result<Vec<u8>> {
+    anyhow::ensure!(enc.kdf == "keyfile" && enc.cipher == "aes-256-gcm", "Unsupported backup format");
+
+    if let Some(blob_fp) = &enc.key_fingerprint {
+        anyhow::ensure!(
+            blob_fp == fingerprint,
+            "key mismatch: backup was encrypted with a different key (expected {fingerprint}, blob has {blob_fp})"
+        );
+    }
+
+    let nonce = B64.decode(&enc.nonce_b64).map_err(|e| anyhow::anyhow!("nonce b64: {e}"))?;
+    let ct = B64.decode(&enc.ct_b64).map_err(|e| anyhow::anyhow!("ct b64: {e}"))?;
+
+    let key = Key::<Aes256Gcm>::from_slice(key_bytes);
+    let cipher = Aes256Gcm::new(key);
+
+    cipher
+        .decrypt(GenericArray::from_slice(&nonce), ct.as_ref())
+        .map_err(|_e| anyhow::anyhow!("aes-gcm decrypt failed"))
+}
+
+/* ---------- Keyfile-based master key ---------- */
+
+fn fingerprint_of(key: &[u8]) -> String {
+    Sha256::digest(key)[..8]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// A random 32-byte master key, wrapped (encrypted) under a password-derived
+/// key so it can be unlocked once and reused to encrypt many backups.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct KeyConfig {
+    wrapped: EncBlob,
+    fingerprint: String,
+}
+
+/// Generate a new random master key, wrap it with `password`, and write the
+/// resulting `KeyConfig` to `path`. Returns the key's fingerprint.
+pub fn generate_keyfile(path: &str, password: &str) -> Result<String> {
+    let mut key = [0u8; 32];
+    getrandom::fill(&mut key).map_err(|e| anyhow::anyhow!("getrandom key: {e}"))?;
+
+    let fingerprint = fingerprint_of(&key);
+    let wrapped = encrypt(password, &key)?;
+    let cfg = KeyConfig { wrapped, fingerprint: fingerprint.clone() };
+
+    let bytes = serde_json::to_vec_pretty(&cfg).context("serialize key config")?;
+    std::fs::write(path, bytes).context("write keyfile")?;
+    Ok(fingerprint)
+}
+
+/// Unlock the keyfile at `path` with `password`, returning the raw master
+/// key and its fingerprint.
+pub fn unlock_keyfile(path: &str, password: &str) -> Result<([u8; 32], String)> {
+    let bytes = std::fs::read(path).context("read keyfile")?;
+    let cfg: KeyConfig = serde_json::from_slice(&bytes).context("parse keyfile")?;
+    let raw = decrypt(password, &cfg.wrapped).context("unlock keyfile (wrong password?)")?;
+
+    anyhow::ensure!(raw.len() == 32, "corrupt keyfile: unexpected key length");
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&raw);
+
+    let actual = fingerprint_of(&key);
+    anyhow::ensure!(actual == cfg.fingerprint, "corrupt keyfile: fingerprint mismatch");
+    Ok((key, actual))
+}
+
+/// Like `export_to_file`, but encrypts with an unlocked keyfile master key
+/// instead of re-deriving from a password every time.
+pub async fn export_to_file_keyed(db: &Db, path: &str, key: &[u8; 32], fingerprint: &str) -> Result<()> {
+    let data = dump_backup_data(db).await?;
+    let json = serde_json::to_vec(&data).context("serialize backup json")?;
+    let enc = encrypt_with_key(key, fingerprint, &json)?;
+    let blob = serde_json::to_vec_pretty(&enc).context("serialize enc blob")?;
+    std::fs::write(path, blob).context("write file")?;
+    Ok(())
+}
+
+/// Like `import_from_file`, but unlocks with a keyfile master key and fails
+/// fast on a fingerprint mismatch before attempting AES-GCM decryption.
+pub async fn import_from_file_keyed(db: &Db, path: &str, key: &[u8; 32], fingerprint: &str) -> Result<()> {
+    let bytes = std::fs::read(path).context("read file")?;
+    let enc: EncBlob = serde_json::from_slice(&bytes).context("parse enc blob")?;
+    let pt = decrypt_with_key(key, fingerprint, &enc).context("decrypt")?;
+    let data: BackupData = serde_json::from_slice(&pt).context("parse backup json")?;
+    apply_backup_data(db, data).await
+}
+
+/* ---------- Content-defined chunking (incremental backups) ---------- */
+
+// Target ~1-4 MiB chunks: boundary declared once a chunk is at least
+// CHUNK_MIN long and the low CHUNK_MASK_BITS bits of the rolling hash are
+// zero, with a hard cutoff at CHUNK_MAX to bound pathological runs.
+const CHUNK_MIN: usize = 1024 * 1024;
+const CHUNK_MAX: usize = 4 * 1024 * 1024;
+const CHUNK_MASK_BITS: u32 = 21;
+const CHUNK_WINDOW: usize = 64;
+
+/// Buzhash rolling hash over a sliding window of `CHUNK_WINDOW` bytes.
+struct BuzHash {
+    table: [u32; 256],
+    window: [u8; CHUNK_WINDOW],
+    pos: usize,
+    hash: u32,
+}
+
+impl BuzHash {
+    fn new() -> Self {
+        // Deterministic pseudo-random table (xorshift32) so chunk
+        // boundaries - and therefore dedup - are stable across runs/builds.
+        let mut table = [0u32; 256];
+        let mut seed: u32 = 0x9E37_79B9;
+        for slot in table.iter_mut() {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            *slot = seed;
+        }
+        Self { table, window: [0; CHUNK_WINDOW], pos: 0, hash: 0 }
+    }
+
+    fn roll(&mut self, byte: u8) -> u32 {
+        let outgoing = self.window[self.pos];
+        self.window[self.pos] = byte;
+        self.pos = (self.pos + 1) % CHUNK_WINDOW;
+        let leaving = self.table[outgoing as usize].rotate_left(CHUNK_WINDOW as u32);
+        self.hash = self.hash.rotate_left(1) ^ leaving ^ self.table[byte as usize];
+        self.hash
+    }
+}
+
+/// Split `data` into content-defined chunks using a rolling-hash splitter.
+fn split_chunks(data: &[u8]) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let mask = (1u32 << CHUNK_MASK_BITS) - 1;
+    let mut hasher = BuzHash::new();
+    let mut chunks = Vec::new();
+    let mut start = 0usize;
+
+    for i in 0..data.len() {
+        let h = hasher.roll(data[i]);
+        let len = i - start + 1;
+        if len >= CHUNK_MAX || (len >= CHUNK_MIN && h & mask == 0) {
+            chunks.push(&data[start..=i]);
+            start = i + 1;
+            hasher = BuzHash::new();
+        }
+    }
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+    chunks
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
 
+/// Encrypts `bytes` and writes the blob to `chunks_dir/<sha256-of-plaintext>`
+/// unless that chunk already exists. The digest (and therefore dedup) is
+/// keyed on the plaintext, so identical content always lands on the same
+/// file even though each encryption uses a fresh nonce.
+fn store_chunk(chunks_dir: &Path, password: &str, bytes: &[u8]) -> Result<String> {
+    std::fs::create_dir_all(chunks_dir).context("create chunks dir")?;
+    let digest = sha256_hex(bytes);
+    let path = chunks_dir.join(&digest);
+    if !path.exists() {
+        let enc = encrypt(password, bytes)?;
+        let blob = serde_json::to_vec(&enc).context("serialize enc blob")?;
+        std::fs::write(&path, blob).with_context(|| format!("write chunk {digest}"))?;
+    }
+    Ok(digest)
+}
+
+fn load_chunk(chunks_dir: &Path, password: &str, digest: &str) -> Result<Vec<u8>> {
+    let blob = std::fs::read(chunks_dir.join(digest)).with_context(|| format!("read chunk {digest}"))?;
+    let enc: EncBlob = serde_json::from_slice(&blob).with_context(|| format!("parse chunk {digest}"))?;
+    decrypt(password, &enc).with_context(|| format!("decrypt chunk {digest}"))
+}
+
+/* ---------- Shared restore path ---------- */
+
+async fn apply_backup_data(db: &Db, data: BackupData) -> Result<()> {
+    for (name, docs) in data.collections {
+        let _ = db.db.run_command(doc! { "drop": &name }).await; // ignorieren, wenn es die Collection (noch) nicht gibt
+        if !docs.is_empty() {
+            let coll = db.db.collection::<Document>(&name);
+            coll.insert_many(docs)
+                .await
+                .with_context(|| format!("insert_many into {}", name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Every collection a full backup (of any format) covers.
+const COLLECTION_NAMES: [&str; 6] = [
+    "settings",
+    "suppliers",
+    "categories",
+    "dishes",
+    "orders",
+    "users",
+];
+
+async fn dump_backup_data(db: &Db) -> Result<BackupData> {
     let mut map = BTreeMap::<String, Vec<Document>>::new();
-    for n in names {
+    for n in COLLECTION_NAMES {
         map.insert(n.to_string(), dump_collection(db, n).await?);
     }
 
-    let data = BackupData {
+    Ok(BackupData {
         meta: Meta {
             created_at: DateTime::now(),
             app: "BestellDesk".into(),
             version: 1,
         },
         collections: map,
-    };
+    })
+}
+
+/* ---------- Public API ---------- */
+
+pub async fn export_to_file(db: &Db, path: &str, password: &str) -> Result<()> {
+    let data = dump_backup_data(db).await?;
 
     let json = serde_json::to_vec(&data).context("serialize backup json")?;
     let enc = encrypt(password, &json)?;
@@ -160,21 +412,782 @@ pub async fn export_to_file(db: &Db, path: &str, password: &str) -> Result<()> {
     Ok(())
 }
 
-pub async fn import_from_file(db: &Db, path: &str, password: &str) -> Result<()> {
+/// Writes an encrypted snapshot of the current DB to a timestamped file
+/// before a destructive import, so a bad import can be undone by running
+/// `import_from_file` on the snapshot. Returns the snapshot path.
+async fn write_safety_snapshot(db: &Db, password: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(format!(
+        "bestelldesk-snapshot-{}.json.enc",
+        DateTime::now().timestamp_millis()
+    ));
+    export_to_file(db, path.to_str().context("snapshot path is not valid UTF-8")?, password).await?;
+    Ok(path)
+}
+
+/// Replaces the live DB with `data`, first writing a safety snapshot of what
+/// was there and rolling back to it if the replace fails partway through.
+/// Returns the snapshot path so the caller can surface it for manual
+/// recovery even when the import itself succeeds.
+async fn apply_backup_data_guarded(db: &Db, data: BackupData, password: &str) -> Result<PathBuf> {
+    let snapshot_path = write_safety_snapshot(db, password).await?;
+    if let Err(e) = apply_backup_data(db, data).await {
+        let snapshot_str = snapshot_path.to_string_lossy().to_string();
+        match load_backup_file(&snapshot_str, password).context("reload safety snapshot for rollback") {
+            Ok(snapshot) => {
+                apply_backup_data(db, snapshot)
+                    .await
+                    .context("restore safety snapshot after failed import")?;
+                return Err(e.context(format!(
+                    "import failed, rolled back to snapshot {snapshot_str}"
+                )));
+            }
+            Err(rollback_err) => {
+                return Err(e.context(format!(
+                    "import failed AND automatic rollback failed ({rollback_err:#}); DB may be inconsistent, snapshot kept at {snapshot_str}"
+                )));
+            }
+        }
+    }
+    Ok(snapshot_path)
+}
+
+/// Replaces the live DB with the backup at `path`. Writes a timestamped
+/// safety snapshot first and rolls back to it automatically if the import
+/// fails partway through; returns the snapshot path on success so the caller
+/// can recover manually if the import turns out to be wrong anyway.
+pub async fn import_from_file(db: &Db, path: &str, password: &str) -> Result<String> {
+    let data = load_backup_file(path, password)?;
+    let snapshot_path = apply_backup_data_guarded(db, data, password).await?;
+    Ok(snapshot_path.to_string_lossy().to_string())
+}
+
+fn load_backup_file(path: &str, password: &str) -> Result<BackupData> {
     let bytes = std::fs::read(path).context("read file")?;
     let enc: EncBlob = serde_json::from_slice(&bytes).context("parse enc blob")?;
     let pt = decrypt(password, &enc).context("decrypt")?;
+    serde_json::from_slice(&pt).context("parse backup json")
+}
+
+/// Like `import_from_file`, but `url` is an `http://`/`https://` URL
+/// instead of a local path: the encrypted blob is fetched into memory, then
+/// run through the same snapshot-guarded decrypt-and-replace path. Lets
+/// teams keep a single canonical encrypted backup on an internal server and
+/// have each client pull the latest state by pasting a link.
+pub async fn import_from_url(db: &Db, url: &str, password: &str) -> Result<String> {
+    let bytes = reqwest::get(url)
+        .await
+        .with_context(|| format!("fetch {url}"))?
+        .error_for_status()
+        .with_context(|| format!("fetch {url}"))?
+        .bytes()
+        .await
+        .with_context(|| format!("read body of {url}"))?;
+    let enc: EncBlob = serde_json::from_slice(&bytes).context("parse enc blob")?;
+    let pt = decrypt(password, &enc).context("decrypt")?;
     let data: BackupData = serde_json::from_slice(&pt).context("parse backup json")?;
+    let snapshot_path = apply_backup_data_guarded(db, data, password).await?;
+    Ok(snapshot_path.to_string_lossy().to_string())
+}
+
+/// Dispatches to `import_from_url` or `import_from_file` depending on
+/// whether `source` looks like an `http://`/`https://` URL or a local path.
+pub async fn import_from_source(db: &Db, source: &str, password: &str) -> Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        import_from_url(db, source, password).await
+    } else {
+        import_from_file(db, source, password).await
+    }
+}
+
+/* ---------- Streamed, versioned container with progress reporting ---------- */
+
+/// Identifies a file as a streamed BestellDesk backup container before any
+/// decryption is attempted.
+const MAGIC_MARKER: &[u8; 8] = b"BDKSTRM\0";
+/// Container format version. `import_streamed` refuses to read anything
+/// newer than this, so old clients fail loudly instead of misreading bytes.
+const FILE_VERSION: u8 = 1;
+
+/// Progress/result messages sent from the export/import worker thread
+/// (see `export_streamed`/`import_streamed`) back to the UI thread.
+pub enum Op {
+    Progress { done: usize, total: usize },
+    Done,
+    Err(String),
+}
+
+/// Writes `MAGIC_MARKER` + `FILE_VERSION`, then one length-prefixed encrypted
+/// chunk for the meta block and one per entry in `COLLECTION_NAMES`, sending
+/// an `Op::Progress` after each chunk so large databases stay observable.
+fn write_streamed(
+    path: &Path,
+    password: &str,
+    data: &BackupData,
+    tx: &std::sync::mpsc::SyncSender<Op>,
+) -> Result<()> {
+    use std::io::Write;
+
+    let total = COLLECTION_NAMES.len() + 1; // + meta chunk
+    let mut done = 0usize;
+    let mut file = std::fs::File::create(path).context("create file")?;
+    file.write_all(MAGIC_MARKER).context("write magic")?;
+    file.write_all(&[FILE_VERSION]).context("write version")?;
+
+    let mut write_chunk = |plaintext: &[u8]| -> Result<()> {
+        let enc = encrypt(password, plaintext)?;
+        let blob = serde_json::to_vec(&enc).context("serialize enc blob")?;
+        file.write_all(&(blob.len() as u32).to_le_bytes()).context("write chunk length")?;
+        file.write_all(&blob).context("write chunk")?;
+        Ok(())
+    };
+
+    write_chunk(&serde_json::to_vec(&data.meta).context("serialize meta")?)?;
+    done += 1;
+    let _ = tx.send(Op::Progress { done, total });
+
+    for name in COLLECTION_NAMES {
+        let docs = data.collections.get(name).cloned().unwrap_or_default();
+        write_chunk(&serde_json::to_vec(&docs).context("serialize collection")?)?;
+        done += 1;
+        let _ = tx.send(Op::Progress { done, total });
+    }
+
+    Ok(())
+}
+
+fn read_chunk(file: &mut std::fs::File, password: &str) -> Result<Vec<u8>> {
+    use std::io::Read;
+    let mut len_buf = [0u8; 4];
+    file.read_exact(&mut len_buf).context("read chunk length")?;
+    let mut blob = vec![0u8; u32::from_le_bytes(len_buf) as usize];
+    file.read_exact(&mut blob).context("read chunk")?;
+    let enc: EncBlob = serde_json::from_slice(&blob).context("parse enc blob")?;
+    decrypt(password, &enc).context("decrypt chunk")
+}
+
+/// Validates the magic marker and version header, then reads the meta and
+/// collection chunks written by `write_streamed`, reporting progress on `tx`.
+fn read_streamed(path: &Path, password: &str, tx: &std::sync::mpsc::SyncSender<Op>) -> Result<BackupData> {
+    use std::io::Read;
+
+    let total = COLLECTION_NAMES.len() + 1;
+    let mut done = 0usize;
+    let mut file = std::fs::File::open(path).context("open file")?;
+
+    let mut magic = [0u8; 8];
+    file.read_exact(&mut magic).context("read magic")?;
+    anyhow::ensure!(&magic == MAGIC_MARKER, "not a BestellDesk streamed backup (bad magic header)");
+
+    let mut ver = [0u8; 1];
+    file.read_exact(&mut ver).context("read version")?;
+    anyhow::ensure!(
+        ver[0] == FILE_VERSION,
+        "unsupported backup format version {} (this build supports version {FILE_VERSION}); update BestellDesk before importing",
+        ver[0]
+    );
+
+    let meta: Meta = serde_json::from_slice(&read_chunk(&mut file, password)?).context("parse meta")?;
+    done += 1;
+    let _ = tx.send(Op::Progress { done, total });
+
+    let mut collections = BTreeMap::<String, Vec<Document>>::new();
+    for name in COLLECTION_NAMES {
+        let docs: Vec<Document> = serde_json::from_slice(&read_chunk(&mut file, password)?).context("parse collection")?;
+        collections.insert(name.to_string(), docs);
+        done += 1;
+        let _ = tx.send(Op::Progress { done, total });
+    }
+
+    Ok(BackupData { meta, collections })
+}
+
+/// Dumps the DB and writes it as a streamed container on a dedicated worker
+/// thread, returning immediately with a `Receiver` the UI can poll each
+/// frame instead of blocking on `rt.block_on` for the whole export.
+pub fn export_streamed(
+    handle: tokio::runtime::Handle,
+    db: Db,
+    path: String,
+    password: String,
+) -> std::sync::mpsc::Receiver<Op> {
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Op>(8);
+    std::thread::spawn(move || {
+        let result: Result<()> = (|| {
+            let data = handle.block_on(dump_backup_data(&db))?;
+            write_streamed(Path::new(&path), &password, &data, &tx)
+        })();
+        match result {
+            Ok(_) => { let _ = tx.send(Op::Done); }
+            Err(e) => { let _ = tx.send(Op::Err(format!("{e:#}"))); }
+        }
+    });
+    rx
+}
+
+/// Like `export_streamed`, but reads a streamed container and replaces the
+/// live DB with its contents, again reporting progress from a worker thread.
+pub fn import_streamed(
+    handle: tokio::runtime::Handle,
+    db: Db,
+    path: String,
+    password: String,
+) -> std::sync::mpsc::Receiver<Op> {
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Op>(8);
+    std::thread::spawn(move || {
+        let result: Result<()> = (|| {
+            let data = read_streamed(Path::new(&path), &password, &tx)?;
+            handle.block_on(apply_backup_data(&db, data))
+        })();
+        match result {
+            Ok(_) => { let _ = tx.send(Op::Done); }
+            Err(e) => { let _ = tx.send(Op::Err(format!("{e:#}"))); }
+        }
+    });
+    rx
+}
+
+/* ---------- Catalog / selective restore ---------- */
+
+#[derive(Debug, Clone)]
+pub struct CollectionEntry {
+    pub name: String,
+    pub doc_count: usize,
+    pub byte_size: usize,
+}
+
+fn collection_entries(data: &BackupData) -> Vec<CollectionEntry> {
+    let mut out = Vec::new();
+    for (name, docs) in &data.collections {
+        let byte_size: usize = docs
+            .iter()
+            .map(|d| mongodb::bson::to_vec(d).map(|b| b.len()).unwrap_or(0))
+            .sum();
+        out.push(CollectionEntry { name: name.clone(), doc_count: docs.len(), byte_size });
+    }
+    out
+}
+
+/// Decrypt `path` and list its collections (name, doc count, byte size)
+/// without touching the live DB.
+pub fn list_contents(path: &str, password: &str) -> Result<Vec<CollectionEntry>> {
+    let data = load_backup_file(path, password)?;
+    Ok(collection_entries(&data))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Drop the collection and insert_many (current `import_from_file` behavior).
+    Replace,
+    /// Upsert each document by `_id`, leaving unrelated documents intact.
+    Merge,
+}
+
+/// Restore only `selection` from `path`, leaving every other live collection
+/// untouched.
+pub async fn import_selective(
+    db: &Db,
+    path: &str,
+    password: &str,
+    selection: &std::collections::HashSet<String>,
+    mode: ImportMode,
+) -> Result<()> {
+    let data = load_backup_file(path, password)?;
 
-    // Replace all: drop + insert_many
     for (name, docs) in data.collections {
-        let _ = db.db.run_command(doc! { "drop": &name }).await; // ignorieren, wenn es die Collection (noch) nicht gibt
-        if !docs.is_empty() {
-            let coll = db.db.collection::<Document>(&name);
-            coll.insert_many(docs)
-                .await
-                .with_context(|| format!("insert_many into {}", name))?;
+        if !selection.contains(&name) {
+            continue;
+        }
+        let coll = db.db.collection::<Document>(&name);
+        match mode {
+            ImportMode::Replace => {
+                let _ = db.db.run_command(doc! { "drop": &name }).await;
+                if !docs.is_empty() {
+                    coll.insert_many(docs)
+                        .await
+                        .with_context(|| format!("insert_many into {}", name))?;
+                }
+            }
+            ImportMode::Merge => {
+                for d in docs {
+                    if let Some(id) = d.get("_id") {
+                        coll.replace_one(doc! { "_id": id.clone() }, d)
+                            .upsert(true)
+                            .await
+                            .with_context(|| format!("upsert into {}", name))?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Incremental backup: serialize the same collections as `export_to_file`,
+/// split the JSON into content-defined chunks, and only write the ones not
+/// already present under `<backup_dir>/chunks/`. Every chunk is individually
+/// AES-256-GCM encrypted (like the index), so the chunk store never holds
+/// plaintext.
+pub async fn export_incremental(db: &Db, backup_dir: &str, password: &str) -> Result<PathBuf> {
+    let data = dump_backup_data(db).await?;
+    let created_at = data.meta.created_at;
+
+    let json = serde_json::to_vec(&data).context("serialize backup json")?;
+    let dir = Path::new(backup_dir);
+    let chunks_dir = dir.join("chunks");
+
+    let chunks = split_chunks(&json)
+        .into_iter()
+        .map(|c| store_chunk(&chunks_dir, password, c))
+        .collect::<Result<Vec<String>>>()?;
+
+    let index = ChunkIndex { meta: data.meta, chunks };
+    let index_json = serde_json::to_vec(&index).context("serialize chunk index")?;
+    let enc = encrypt(password, &index_json)?;
+    let blob = serde_json::to_vec_pretty(&enc).context("serialize enc blob")?;
+
+    std::fs::create_dir_all(dir).context("create backup dir")?;
+    let index_path = dir.join(format!("{}.bdkidx", created_at.timestamp_millis()));
+    std::fs::write(&index_path, blob).context("write index file")?;
+    Ok(index_path)
+}
+
+/// Reassemble and restore an incremental backup written by `export_incremental`.
+pub async fn import_incremental(db: &Db, index_path: &str, password: &str) -> Result<()> {
+    let index_path = Path::new(index_path);
+    let chunks_dir = index_path
+        .parent()
+        .map(|p| p.join("chunks"))
+        .context("index path has no parent directory")?;
+
+    let bytes = std::fs::read(index_path).context("read index file")?;
+    let enc: EncBlob = serde_json::from_slice(&bytes).context("parse enc blob")?;
+    let pt = decrypt(password, &enc).context("decrypt")?;
+    let index: ChunkIndex = serde_json::from_slice(&pt).context("parse chunk index")?;
+
+    let mut json = Vec::new();
+    for digest in &index.chunks {
+        json.extend(load_chunk(&chunks_dir, password, digest)?);
+    }
+    let data: BackupData = serde_json::from_slice(&json).context("parse backup json")?;
+
+    apply_backup_data(db, data).await
+}
+
+/* ---------- Pluggable backup target (local file or S3) ---------- */
+
+use crate::services::backup_target::{self, BackupTarget};
+
+/// Like `export_to_file`, but writes the encrypted blob to any `BackupTarget`
+/// (local file or S3-compatible object storage) instead of a bare path.
+pub async fn export_to_target(db: &Db, target: &BackupTarget, key: &str, password: &str) -> Result<()> {
+    let data = dump_backup_data(db).await?;
+    let json = serde_json::to_vec(&data).context("serialize backup json")?;
+    let enc = encrypt(password, &json)?;
+    let blob = serde_json::to_vec_pretty(&enc).context("serialize enc blob")?;
+    backup_target::put_object(target, key, &blob).await
+}
+
+pub async fn import_from_target(db: &Db, target: &BackupTarget, key: &str, password: &str) -> Result<()> {
+    let blob = backup_target::get_object(target, key).await?;
+    let enc: EncBlob = serde_json::from_slice(&blob).context("parse enc blob")?;
+    let pt = decrypt(password, &enc).context("decrypt")?;
+    let data: BackupData = serde_json::from_slice(&pt).context("parse backup json")?;
+    apply_backup_data(db, data).await
+}
+
+/// Like `list_contents`, but `key` is fetched from `target` (local file or
+/// S3-compatible object storage) instead of read from a local path.
+pub async fn list_contents_from_target(
+    target: &BackupTarget,
+    key: &str,
+    password: &str,
+) -> Result<Vec<CollectionEntry>> {
+    let blob = backup_target::get_object(target, key).await?;
+    let enc: EncBlob = serde_json::from_slice(&blob).context("parse enc blob")?;
+    let pt = decrypt(password, &enc).context("decrypt")?;
+    let data: BackupData = serde_json::from_slice(&pt).context("parse backup json")?;
+    Ok(collection_entries(&data))
+}
+
+/// Incremental export that uploads new chunks and the index to `target`,
+/// using conditional PUT so dedup also works across the network.
+pub async fn export_incremental_to_target(
+    db: &Db,
+    target: &BackupTarget,
+    index_key: &str,
+    password: &str,
+) -> Result<()> {
+    let data = dump_backup_data(db).await?;
+    let json = serde_json::to_vec(&data).context("serialize backup json")?;
+
+    let mut chunks = Vec::new();
+    for chunk in split_chunks(&json) {
+        let digest = sha256_hex(chunk);
+        let enc = encrypt(password, chunk)?;
+        let blob = serde_json::to_vec(&enc).context("serialize enc blob")?;
+        backup_target::put_object(target, &format!("chunks/{digest}"), &blob).await?;
+        chunks.push(digest);
+    }
+
+    let index = ChunkIndex { meta: data.meta, chunks };
+    let index_json = serde_json::to_vec(&index).context("serialize chunk index")?;
+    let enc = encrypt(password, &index_json)?;
+    let blob = serde_json::to_vec_pretty(&enc).context("serialize enc blob")?;
+    backup_target::put_object(target, index_key, &blob).await
+}
+
+pub async fn import_incremental_from_target(
+    db: &Db,
+    target: &BackupTarget,
+    index_key: &str,
+    password: &str,
+) -> Result<()> {
+    let blob = backup_target::get_object(target, index_key).await?;
+    let enc: EncBlob = serde_json::from_slice(&blob).context("parse enc blob")?;
+    let pt = decrypt(password, &enc).context("decrypt")?;
+    let index: ChunkIndex = serde_json::from_slice(&pt).context("parse chunk index")?;
+
+    let mut json = Vec::new();
+    for digest in &index.chunks {
+        let blob = backup_target::get_object(target, &format!("chunks/{digest}")).await?;
+        let chunk_enc: EncBlob = serde_json::from_slice(&blob).context("parse chunk enc blob")?;
+        json.extend(decrypt(password, &chunk_enc).context("decrypt chunk")?);
+    }
+    let data: BackupData = serde_json::from_slice(&json).context("parse backup json")?;
+    apply_backup_data(db, data).await
+}
+
+/* ---------- Retention / prune policy ---------- */
+
+/// How many snapshots to keep per bucket size, applied to timestamped
+/// `.bdkidx` backup indexes (see `export_incremental`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionSpec {
+    pub keep_last: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+    pub keep_yearly: usize,
+}
+
+pub struct PruneReport {
+    pub kept: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+struct Snapshot {
+    path: PathBuf,
+    created_at_millis: i64,
+}
+
+/// Days since the Unix epoch (1970-01-01) for a given millis timestamp.
+fn days_since_epoch(millis: i64) -> i64 {
+    millis.div_euclid(86_400_000)
+}
+
+/// Howard Hinnant's `civil_from_days`: converts a day count since the
+/// epoch into a proleptic-Gregorian (year, month, day).
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as i64; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32; // [1, 31]
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32; // [1, 12]
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+/// ISO weekday, 1 (Monday) .. 7 (Sunday).
+fn iso_weekday(days: i64) -> i64 {
+    // 1970-01-01 (days == 0) was a Thursday.
+    ((days % 7 + 7) % 7 + 3) % 7 + 1
+}
+
+fn iso_weeks_in_year(y: i64) -> i64 {
+    let p = |y: i64| (y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400)).rem_euclid(7);
+    if p(y) == 4 || p(y - 1) == 3 { 53 } else { 52 }
+}
+
+/// ISO-8601 (year, week) for a given day count since the epoch.
+fn iso_week(days: i64) -> (i64, i64) {
+    let (y, m, d) = civil_from_days(days);
+    let days_before_month: [i64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+    let leap = (y % 4 == 0 && y % 100 != 0) || y % 400 == 0;
+    let mut ordinal = days_before_month[(m - 1) as usize] + d as i64;
+    if leap && m > 2 {
+        ordinal += 1;
+    }
+    let weekday = iso_weekday(days);
+    let mut week = (ordinal - weekday + 10).div_euclid(7);
+    let mut year = y;
+    if week < 1 {
+        year -= 1;
+        week = iso_weeks_in_year(year);
+    } else if week > iso_weeks_in_year(y) {
+        year += 1;
+        week = 1;
+    }
+    (year, week)
+}
+
+fn day_bucket(millis: i64) -> String {
+    let (y, m, d) = civil_from_days(days_since_epoch(millis));
+    format!("{y:04}-{m:02}-{d:02}")
+}
+fn week_bucket(millis: i64) -> String {
+    let (y, w) = iso_week(days_since_epoch(millis));
+    format!("{y:04}-W{w:02}")
+}
+fn month_bucket(millis: i64) -> String {
+    let (y, m, _) = civil_from_days(days_since_epoch(millis));
+    format!("{y:04}-{m:02}")
+}
+fn year_bucket(millis: i64) -> String {
+    let (y, _, _) = civil_from_days(days_since_epoch(millis));
+    format!("{y:04}")
+}
+
+/// Decrypt just enough of each `.bdkidx` file in `dir` to read its `Meta`,
+/// without touching the chunk store or the live DB.
+fn list_snapshots(dir: &Path, password: &str) -> Result<Vec<Snapshot>> {
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(dir).context("read backup dir")? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("bdkidx") {
+            continue;
+        }
+        let bytes = std::fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+        let enc: EncBlob = serde_json::from_slice(&bytes)
+            .with_context(|| format!("parse {}", path.display()))?;
+        let pt = decrypt(password, &enc)
+            .with_context(|| format!("decrypt {}", path.display()))?;
+        let index: ChunkIndex = serde_json::from_slice(&pt)
+            .with_context(|| format!("parse index {}", path.display()))?;
+        out.push(Snapshot {
+            path,
+            created_at_millis: index.meta.created_at.timestamp_millis(),
+        });
+    }
+    out.sort_by_key(|s| std::cmp::Reverse(s.created_at_millis));
+    Ok(out)
+}
+
+/// Apply a bucketed retention policy to the timestamped backup indexes in
+/// `dir`, deleting those that fall outside it. When `dry_run` is set,
+/// nothing is deleted and the report just previews what would happen.
+pub fn prune(dir: &str, password: &str, spec: &RetentionSpec, dry_run: bool) -> Result<PruneReport> {
+    let snapshots = list_snapshots(Path::new(dir), password)?;
+
+    let mut kept = vec![false; snapshots.len()];
+    let mut remaining_last = spec.keep_last;
+    for k in kept.iter_mut().take(snapshots.len()) {
+        if remaining_last == 0 {
+            break;
         }
+        *k = true;
+        remaining_last -= 1;
     }
+
+    let mut remaining_daily = spec.keep_daily;
+    let mut remaining_weekly = spec.keep_weekly;
+    let mut remaining_monthly = spec.keep_monthly;
+    let mut remaining_yearly = spec.keep_yearly;
+    let mut seen_daily = std::collections::HashSet::new();
+    let mut seen_weekly = std::collections::HashSet::new();
+    let mut seen_monthly = std::collections::HashSet::new();
+    let mut seen_yearly = std::collections::HashSet::new();
+
+    for (i, snap) in snapshots.iter().enumerate() {
+        if kept[i] {
+            continue;
+        }
+        let millis = snap.created_at_millis;
+        let mut selected = false;
+
+        if remaining_daily > 0 {
+            let key = day_bucket(millis);
+            if seen_daily.insert(key) {
+                remaining_daily -= 1;
+                selected = true;
+            }
+        }
+        if remaining_weekly > 0 {
+            let key = week_bucket(millis);
+            if seen_weekly.insert(key) {
+                remaining_weekly -= 1;
+                selected = true;
+            }
+        }
+        if remaining_monthly > 0 {
+            let key = month_bucket(millis);
+            if seen_monthly.insert(key) {
+                remaining_monthly -= 1;
+                selected = true;
+            }
+        }
+        if remaining_yearly > 0 {
+            let key = year_bucket(millis);
+            if seen_yearly.insert(key) {
+                remaining_yearly -= 1;
+                selected = true;
+            }
+        }
+        kept[i] = selected;
+    }
+
+    let mut report = PruneReport { kept: Vec::new(), removed: Vec::new() };
+    for (snap, keep) in snapshots.into_iter().zip(kept) {
+        if keep {
+            report.kept.push(snap.path);
+        } else {
+            if !dry_run {
+                let _ = std::fs::remove_file(&snap.path);
+            }
+            report.removed.push(snap.path);
+        }
+    }
+    Ok(report)
+}
+
+/* ---------- Plain CSV export (for Excel/LibreOffice, not encrypted) ---------- */
+
+/// Which domain collection `export_csv` writes out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvCollection {
+    Suppliers,
+    Orders,
+    OrderItems,
+}
+
+/// Resolves a `Supplier` reference to its display name, falling back to the
+/// hex id if the supplier was since deleted.
+fn id_to_name(sups: &[Supplier], id: ObjectId) -> String {
+    sups.iter()
+        .find(|s| s.id == Some(id))
+        .map(|s| s.name.clone())
+        .unwrap_or_else(|| id.to_hex())
+}
+
+#[derive(serde::Serialize)]
+struct SupplierRow {
+    name: String,
+    delivery_fee_cents: i64,
+}
+
+#[derive(serde::Serialize)]
+struct OrderRow {
+    order_code: String,
+    customer_name: String,
+    supplier: String,
+    items_total_cents: i64,
+    delivery_fee_cents: i64,
+    grand_total_cents: i64,
+    status: String,
+}
+
+#[derive(serde::Serialize)]
+struct OrderItemRow {
+    order_code: String,
+    supplier: String,
+    dish_name: String,
+    variant: String,
+    qty: i32,
+    unit_price_cents: i64,
+    line_total_cents: i64,
+}
+
+/// Plaintext CSV export of one domain collection, for handing to accounting
+/// without round-tripping through the app. Unlike `export_to_file`, the
+/// column header row is derived straight from the row struct's fields and
+/// `ObjectId` references are resolved to names via `id_to_name`.
+pub async fn export_csv(db: &Db, path: &str, collection: CsvCollection) -> Result<()> {
+    let sups = suppliers::list(db).await?;
+    let mut wtr = csv::Writer::from_path(path).with_context(|| format!("open {path}"))?;
+
+    match collection {
+        CsvCollection::Suppliers => {
+            for s in &sups {
+                wtr.serialize(SupplierRow {
+                    name: s.name.clone(),
+                    delivery_fee_cents: s.delivery_fee_cents,
+                })?;
+            }
+        }
+        CsvCollection::Orders => {
+            for s in &sups {
+                let Some(sid) = s.id else { continue };
+                let supplier = id_to_name(&sups, sid);
+                for o in orders::list_by_supplier(db, sid).await? {
+                    wtr.serialize(OrderRow {
+                        order_code: o.order_code,
+                        customer_name: o.customer_name,
+                        supplier: supplier.clone(),
+                        items_total_cents: o.items_total_cents,
+                        delivery_fee_cents: o.delivery_fee_cents,
+                        grand_total_cents: o.grand_total_cents,
+                        status: o.status,
+                    })?;
+                }
+            }
+        }
+        CsvCollection::OrderItems => {
+            for s in &sups {
+                let Some(sid) = s.id else { continue };
+                let supplier = id_to_name(&sups, sid);
+                for o in orders::list_by_supplier(db, sid).await? {
+                    for item in o.items {
+                        wtr.serialize(OrderItemRow {
+                            order_code: o.order_code.clone(),
+                            supplier: supplier.clone(),
+                            dish_name: item.name,
+                            variant: item.variant.unwrap_or_default(),
+                            qty: item.qty,
+                            unit_price_cents: item.unit_price_cents,
+                            line_total_cents: item.line_total_cents,
+                        })?;
+                    }
+                }
+            }
+        }
+    }
+
+    wtr.flush().context("flush csv")?;
     Ok(())
 }
+
+/// Garbage-collect chunk files under `<dir>/chunks` that are no longer
+/// referenced by any surviving `.bdkidx` index. Run after `prune`.
+pub fn gc_chunks(dir: &str, password: &str) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(dir);
+    let chunks_dir = dir.join("chunks");
+    if !chunks_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut referenced = std::collections::HashSet::new();
+    for entry in std::fs::read_dir(dir).context("read backup dir")? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("bdkidx") {
+            continue;
+        }
+        let bytes = std::fs::read(&path)?;
+        let enc: EncBlob = serde_json::from_slice(&bytes)?;
+        let pt = decrypt(password, &enc)?;
+        let index: ChunkIndex = serde_json::from_slice(&pt)?;
+        referenced.extend(index.chunks);
+    }
+
+    let mut removed = Vec::new();
+    for entry in std::fs::read_dir(&chunks_dir).context("read chunks dir")? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if !referenced.contains(name) {
+            let _ = std::fs::remove_file(&path);
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,185 @@
+// src/services/backup_target.rs
+//
+// Pluggable destination for encrypted backup blobs/chunks: either the local
+// filesystem (current behavior) or S3-compatible object storage, so backups
+// don't have to live on the same machine whose DB they protect.
+
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::model::S3BackupConfig;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone)]
+pub enum BackupTarget {
+    LocalFile { dir: String },
+    S3(S3BackupConfig),
+}
+
+impl BackupTarget {
+    pub fn local(dir: impl Into<String>) -> Self {
+        BackupTarget::LocalFile { dir: dir.into() }
+    }
+
+    pub fn from_settings(cfg: Option<S3BackupConfig>, local_dir: impl Into<String>) -> Self {
+        match cfg {
+            Some(c) => BackupTarget::S3(c),
+            None => BackupTarget::local(local_dir),
+        }
+    }
+}
+
+/// Upload `bytes` under `key` unless an object already exists there
+/// (conditional PUT), so re-running an incremental backup doesn't re-upload
+/// chunks that are already present.
+pub async fn put_object(target: &BackupTarget, key: &str, bytes: &[u8]) -> Result<()> {
+    match target {
+        BackupTarget::LocalFile { dir } => {
+            let path = std::path::Path::new(dir).join(key);
+            if path.exists() {
+                return Ok(());
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).context("create backup target dir")?;
+            }
+            std::fs::write(&path, bytes).with_context(|| format!("write {}", path.display()))
+        }
+        BackupTarget::S3(cfg) => {
+            if object_exists(target, key).await? {
+                return Ok(());
+            }
+            s3_request(cfg, "PUT", key, Some(bytes)).await?;
+            Ok(())
+        }
+    }
+}
+
+pub async fn get_object(target: &BackupTarget, key: &str) -> Result<Vec<u8>> {
+    match target {
+        BackupTarget::LocalFile { dir } => {
+            let path = std::path::Path::new(dir).join(key);
+            std::fs::read(&path).with_context(|| format!("read {}", path.display()))
+        }
+        BackupTarget::S3(cfg) => s3_request(cfg, "GET", key, None).await,
+    }
+}
+
+pub async fn object_exists(target: &BackupTarget, key: &str) -> Result<bool> {
+    match target {
+        BackupTarget::LocalFile { dir } => Ok(std::path::Path::new(dir).join(key).exists()),
+        BackupTarget::S3(cfg) => match s3_request(cfg, "HEAD", key, None).await {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                if e.to_string().contains("404") {
+                    Ok(false)
+                } else {
+                    Err(e)
+                }
+            }
+        },
+    }
+}
+
+/* ---------- Minimal AWS SigV4 client for S3-compatible endpoints ---------- */
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+async fn s3_request(cfg: &S3BackupConfig, method: &str, key: &str, body: Option<&[u8]>) -> Result<Vec<u8>> {
+    let body = body.unwrap_or(&[]);
+    let payload_hash = sha256_hex(body);
+
+    let host = cfg
+        .endpoint
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_string();
+    let url = format!("{}/{}/{}", cfg.endpoint.trim_end_matches('/'), cfg.bucket, key.trim_start_matches('/'));
+
+    // The request is signed with the current system time.
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let (amz_date, date_stamp) = format_amz_timestamps(now.as_secs());
+
+    let canonical_uri = format!("/{}/{}", cfg.bucket, key.trim_start_matches('/'));
+    let canonical_headers = format!(
+        "host:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n"
+    );
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "{method}\n{canonical_uri}\n\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+    );
+
+    let credential_scope = format!("{date_stamp}/{}/s3/aws4_request", cfg.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
+        sha256_hex(canonical_request.as_bytes())
+    );
+
+    let k_date = hmac_sha256(format!("AWS4{}", cfg.secret_key).as_bytes(), date_stamp.as_bytes());
+    let k_region = hmac_sha256(&k_date, cfg.region.as_bytes());
+    let k_service = hmac_sha256(&k_region, b"s3");
+    let k_signing = hmac_sha256(&k_service, b"aws4_request");
+    let signature: String = hmac_sha256(&k_signing, string_to_sign.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+
+    let auth_header = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{credential_scope},SignedHeaders={signed_headers},Signature={signature}",
+        cfg.access_key
+    );
+
+    let client = reqwest::Client::new();
+    let mut req = client
+        .request(reqwest::Method::from_bytes(method.as_bytes())?, &url)
+        .header("x-amz-date", amz_date)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("Authorization", auth_header);
+
+    if !body.is_empty() {
+        req = req.body(body.to_vec());
+    }
+
+    let resp = req.send().await.context("S3 request failed")?;
+    if !resp.status().is_success() {
+        anyhow::bail!("S3 {method} {key} failed: {}", resp.status());
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+/// Formats `(YYYYMMDDTHHMMSSZ, YYYYMMDD)` from Unix seconds, avoiding a chrono
+/// dependency for this one call site.
+fn format_amz_timestamps(unix_secs: u64) -> (String, String) {
+    let days = (unix_secs / 86_400) as i64;
+    let secs_of_day = unix_secs % 86_400;
+    let (h, m, s) = (secs_of_day / 3600, (secs_of_day / 60) % 60, secs_of_day % 60);
+
+    // Howard Hinnant's civil_from_days.
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let mon = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = if mon <= 2 { y + 1 } else { y };
+
+    let date_stamp = format!("{y:04}{mon:02}{d:02}");
+    let amz_date = format!("{date_stamp}T{h:02}{m:02}{s:02}Z");
+    (amz_date, date_stamp)
+}
@@ -1,10 +1,127 @@
+// src/services/categories.rs
+//
+// Categories are ordered by a LexoRank-style fractional `rank` string instead
+// of an integer `position`, so moving one to an arbitrary index only ever
+// touches that single document (no renumbering the rest of the list, no race
+// where two categories land on the same position).
+
+use std::time::Duration;
+
 use anyhow::Result;
 use futures_util::TryStreamExt;
 use mongodb::bson::{doc, oid::ObjectId};
-use mongodb::Collection;
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
 
 use crate::db::Db;
 use crate::model::Category;
+use crate::services::dishes;
+
+/// MongoDB's duplicate-key write-error code.
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+/// A typed failure from `create`/`rename`, so callers (the admin UI) can show
+/// "a category with this name already exists" instead of a generic 500.
+#[derive(Debug)]
+pub enum CategoryError {
+    /// Another category under the same supplier already has this name —
+    /// rejected by the unique `{ supplier_id, name }` index.
+    DuplicateName,
+    Other(anyhow::Error),
+}
+
+impl std::fmt::Display for CategoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CategoryError::DuplicateName => write!(f, "a category with this name already exists"),
+            CategoryError::Other(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for CategoryError {}
+
+impl From<mongodb::error::Error> for CategoryError {
+    fn from(e: mongodb::error::Error) -> Self {
+        if is_duplicate_key_error(&e) {
+            CategoryError::DuplicateName
+        } else {
+            CategoryError::Other(e.into())
+        }
+    }
+}
+
+fn is_duplicate_key_error(e: &mongodb::error::Error) -> bool {
+    matches!(
+        e.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(we)) if we.code == DUPLICATE_KEY_CODE
+    )
+}
+
+/// Base-62 alphabet in ASCII order (`0-9A-Za-z`), so lexicographic string
+/// comparison agrees with the numeric digit ordering used by `rank_between`.
+const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BASE: u64 = 62;
+
+/// Ranks longer than this trigger a rebalance on next `move_to` rather than
+/// growing further — keeps keys from creeping toward unbounded length under
+/// repeated inserts at the same spot.
+const MAX_RANK_LEN: usize = 32;
+
+fn digit_value(c: u8) -> u32 {
+    ALPHABET.iter().position(|&a| a == c).expect("rank contains only base-62 digits") as u32
+}
+
+/// A new rank key that sorts strictly between `prev` and `next` (either may
+/// be absent, meaning "start of list" / "end of list"). Walks both keys
+/// digit by digit, taking the midpoint value as soon as the gap is wide
+/// enough to fit one, and otherwise carrying a matching/one-apart digit
+/// forward to the next position.
+fn rank_between(prev: Option<&str>, next: Option<&str>) -> String {
+    let prev = prev.unwrap_or("");
+    let next = next.unwrap_or("");
+    let mut out = String::new();
+    let mut i = 0;
+    loop {
+        let p = prev.as_bytes().get(i).map(|&c| digit_value(c)).unwrap_or(0);
+        let n = next.as_bytes().get(i).map(|&c| digit_value(c)).unwrap_or(BASE as u32);
+        if n.saturating_sub(p) >= 2 {
+            let mid = p + (n - p) / 2;
+            out.push(ALPHABET[mid as usize] as char);
+            return out;
+        }
+        out.push(ALPHABET[p as usize] as char);
+        i += 1;
+    }
+}
+
+/// `n` evenly spaced rank keys of equal width, in increasing order — used to
+/// rebalance a whole bucket once its keys have grown too long, and to
+/// backfill ranks for categories that still only have a legacy `position`
+/// (see `services::migrations`).
+pub(crate) fn evenly_spaced_ranks(n: usize) -> Vec<String> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut width = 1u32;
+    while BASE.pow(width) < n as u64 + 1 {
+        width += 1;
+    }
+    let step = BASE.pow(width) / (n as u64 + 1);
+    (1..=n as u64)
+        .map(|i| encode_fixed_width(i * step, width as usize))
+        .collect()
+}
+
+fn encode_fixed_width(mut v: u64, width: usize) -> String {
+    let mut chars = vec![b'0'; width];
+    for slot in chars.iter_mut().rev() {
+        *slot = ALPHABET[(v % BASE) as usize];
+        v /= BASE;
+    }
+    String::from_utf8(chars).expect("base-62 alphabet is ASCII")
+}
 
 fn coll(db: &Db) -> Collection<Category> {
     db.collection::<Category>("categories")
@@ -18,61 +135,419 @@ pub async fn list_by_supplier(db: &Db, supplier_id: ObjectId) -> Result<Vec<Cate
     while let Some(c) = cur.try_next().await? {
         out.push(c);
     }
-    out.sort_by_key(|c| (c.position, c.name.clone()));
+    out.sort_by(|a, b| a.rank.cmp(&b.rank).then_with(|| a.name.cmp(&b.name)));
     Ok(out)
 }
 
-pub async fn create(db: &Db, supplier_id: ObjectId, name: &str) -> Result<ObjectId> {
-    let list = list_by_supplier(db, supplier_id).await?;
-    let pos = list.last().map(|c| c.position + 1).unwrap_or(0);
+/// Builds the indexes `list_by_supplier`/`create`/`rename` rely on: a unique
+/// compound index on `{ supplier_id, name }` (so two categories under the
+/// same supplier can't share a name) and one on `{ supplier_id, rank }` to
+/// support the sorted listing. Callable directly, but normally run once via
+/// `services::migrations`.
+pub async fn ensure_indexes(db: &Db) -> Result<()> {
+    let unique_name = IndexModel::builder()
+        .keys(doc! { "supplier_id": 1, "name": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+    let by_rank = IndexModel::builder()
+        .keys(doc! { "supplier_id": 1, "rank": 1 })
+        .build();
+    coll(db).create_indexes([unique_name, by_rank]).await?;
+    Ok(())
+}
+
+pub async fn create(db: &Db, supplier_id: ObjectId, name: &str) -> Result<ObjectId, CategoryError> {
+    let list = list_by_supplier(db, supplier_id).await.map_err(CategoryError::Other)?;
+    let rank = rank_between(list.last().map(|c| c.rank.as_str()), None);
     let c = Category {
         id: None,
         supplier_id,
         name: name.to_string(),
-        position: pos,
+        rank,
     };
     let r = coll(db).insert_one(c).await?;
     Ok(r.inserted_id.as_object_id().unwrap())
 }
 
-pub async fn rename(db: &Db, id: ObjectId, name: &str) -> Result<()> {
+pub async fn rename(db: &Db, id: ObjectId, name: &str) -> Result<(), CategoryError> {
     coll(db)
         .update_one(doc! { "_id": id }, doc! { "$set": { "name": name } })
         .await?;
     Ok(())
 }
 
-pub async fn delete(db: &Db, id: ObjectId) -> Result<()> {
-    coll(db).delete_one(doc! { "_id": id }).await?;
+/// How to resolve dishes still referencing a category being deleted.
+pub enum DeleteStrategy {
+    /// Repoint every affected dish at `ObjectId` instead.
+    Reassign(ObjectId),
+    /// Clear the reference from every affected dish.
+    Detach,
+    /// Refuse the delete if any dish still references the category.
+    Block,
+}
+
+/// Outcome of `delete_with_strategy`: how many dishes were touched by the
+/// chosen strategy before the category itself was removed. Always 0 for
+/// `Block`, since that strategy refuses instead of touching anything.
+pub struct DeleteReport {
+    pub affected_products: u64,
+}
+
+/// Deletes a category, first resolving any dishes that still reference it
+/// according to `strategy` — counts them, applies the strategy with a
+/// single `update_many`, then deletes the category document. `Reassign`
+/// additionally checks that its target category exists under the same
+/// `supplier_id` as the one being deleted, so affected dishes can never end
+/// up pointing at a missing or cross-supplier category.
+pub async fn delete_with_strategy(
+    db: &Db,
+    id: ObjectId,
+    strategy: DeleteStrategy,
+) -> Result<DeleteReport, CategoryError> {
+    let affected = dishes::count_by_category(db, id).await.map_err(CategoryError::Other)?;
+
+    let report = match strategy {
+        DeleteStrategy::Block => {
+            if affected > 0 {
+                return Err(CategoryError::Other(anyhow::anyhow!(
+                    "{affected} product(s) still reference this category"
+                )));
+            }
+            DeleteReport { affected_products: 0 }
+        }
+        DeleteStrategy::Detach => {
+            let n = if affected > 0 {
+                dishes::detach_category(db, id).await.map_err(CategoryError::Other)?
+            } else {
+                0
+            };
+            DeleteReport { affected_products: n }
+        }
+        DeleteStrategy::Reassign(target) => {
+            let category = coll(db)
+                .find_one(doc! { "_id": id })
+                .await
+                .map_err(CategoryError::Other)?
+                .ok_or_else(|| CategoryError::Other(anyhow::anyhow!("category not found")))?;
+            let target_exists = coll(db)
+                .find_one(doc! { "_id": target, "supplier_id": category.supplier_id })
+                .await
+                .map_err(CategoryError::Other)?
+                .is_some();
+            if !target_exists {
+                return Err(CategoryError::Other(anyhow::anyhow!(
+                    "reassign target category does not exist under the same supplier"
+                )));
+            }
+
+            let n = if affected > 0 {
+                dishes::reassign_category(db, id, target).await.map_err(CategoryError::Other)?
+            } else {
+                0
+            };
+            DeleteReport { affected_products: n }
+        }
+    };
+
+    coll(db).delete_one(doc! { "_id": id }).await.map_err(CategoryError::Other)?;
+    Ok(report)
+}
+
+/// Thin wrapper over `delete_with_strategy` defaulting to `Block`, so
+/// existing callers fail loudly instead of silently orphaning dishes.
+pub async fn delete(db: &Db, id: ObjectId) -> Result<(), CategoryError> {
+    delete_with_strategy(db, id, DeleteStrategy::Block).await.map(|_| ())
+}
+
+/// Moves `id` to `target_index` among its supplier's categories (clamped to
+/// the valid range), computing a single new rank key between its new
+/// neighbors. Touches only that one document, unless the new key would
+/// exceed `MAX_RANK_LEN`, in which case the whole bucket is rebalanced.
+pub async fn move_to(db: &Db, supplier_id: ObjectId, id: ObjectId, target_index: usize) -> Result<()> {
+    let mut items = list_by_supplier(db, supplier_id).await?;
+    let Some(cur_idx) = items.iter().position(|c| c.id == Some(id)) else {
+        return Ok(());
+    };
+    let moved = items.remove(cur_idx);
+    let target = target_index.min(items.len());
+
+    let prev = if target == 0 { None } else { items.get(target - 1).map(|c| c.rank.as_str()) };
+    let next = items.get(target).map(|c| c.rank.as_str());
+    let new_rank = rank_between(prev, next);
+
+    if new_rank.len() > MAX_RANK_LEN {
+        items.insert(target, moved);
+        return rebalance_list(db, &items).await;
+    }
+
+    coll(db)
+        .update_one(doc! { "_id": id }, doc! { "$set": { "rank": new_rank } })
+        .await?;
     Ok(())
 }
 
 pub async fn move_up(db: &Db, supplier_id: ObjectId, id: ObjectId) -> Result<()> {
-    move_rel(db, supplier_id, id, -1).await
+    let items = list_by_supplier(db, supplier_id).await?;
+    if let Some(i) = items.iter().position(|c| c.id == Some(id)) {
+        move_to(db, supplier_id, id, i.saturating_sub(1)).await?;
+    }
+    Ok(())
 }
+
 pub async fn move_down(db: &Db, supplier_id: ObjectId, id: ObjectId) -> Result<()> {
-    move_rel(db, supplier_id, id, 1).await
+    let items = list_by_supplier(db, supplier_id).await?;
+    if let Some(i) = items.iter().position(|c| c.id == Some(id)) {
+        // `move_to`'s target index is relative to the list with `id` already
+        // removed, where the next neighbor has shifted down to index `i` —
+        // so `i + 1` (not `i + 2`) lands the move one slot further down.
+        move_to(db, supplier_id, id, i + 1).await?;
+    }
+    Ok(())
 }
 
-async fn move_rel(db: &Db, supplier_id: ObjectId, id: ObjectId, delta: i64) -> Result<()> {
+/// Reassigns evenly spaced rank keys across a supplier's whole category
+/// list. Normally triggered automatically by `move_to`; exposed for callers
+/// (e.g. an admin maintenance action) that want to tidy up keys proactively.
+pub async fn rebalance(db: &Db, supplier_id: ObjectId) -> Result<()> {
     let items = list_by_supplier(db, supplier_id).await?;
-    let idx = items.iter().position(|c| c.id == Some(id));
-    if let Some(i) = idx {
-        let j = if delta < 0 {
-            i.saturating_sub(1)
-        } else {
-            (i + 1).min(items.len().saturating_sub(1))
-        };
-        if i != j {
-            let pi = items[i].position;
-            let pj = items[j].position;
-            coll(db)
-                .update_one(doc!{ "_id": items[i].id.unwrap() }, doc!{ "$set": { "position": pj } })
-                .await?;
+    rebalance_list(db, &items).await
+}
+
+async fn rebalance_list(db: &Db, items: &[Category]) -> Result<()> {
+    let ranks = evenly_spaced_ranks(items.len());
+    for (c, rank) in items.iter().zip(ranks) {
+        if let Some(id) = c.id {
             coll(db)
-                .update_one(doc!{ "_id": items[j].id.unwrap() }, doc!{ "$set": { "position": pi } })
+                .update_one(doc! { "_id": id }, doc! { "$set": { "rank": rank } })
                 .await?;
         }
     }
     Ok(())
 }
+
+const TXN_RETRY_BASE: Duration = Duration::from_millis(50);
+const TXN_RETRY_CAP: Duration = Duration::from_secs(2);
+const TXN_MAX_ATTEMPTS: u32 = 5;
+
+fn is_transient_transaction_error(e: &mongodb::error::Error) -> bool {
+    e.contains_label("TransientTransactionError") || e.contains_label("UnknownTransactionCommitResult")
+}
+
+/// Rewrites every category under `supplier_id` to the ordering implied by
+/// `ordered_ids`, in a single transaction so a crash mid-reorder can never
+/// leave two categories sharing a rank. `ordered_ids` must contain exactly
+/// the supplier's current category ids (in the desired order) — anything
+/// else (missing id, extra id, wrong supplier) is rejected before any write.
+/// Transient transaction errors (e.g. a write conflict with a concurrent
+/// reorder) are retried with backoff up to `TXN_MAX_ATTEMPTS` times.
+pub async fn reorder(db: &Db, supplier_id: ObjectId, ordered_ids: &[ObjectId]) -> Result<(), CategoryError> {
+    let current = list_by_supplier(db, supplier_id).await.map_err(CategoryError::Other)?;
+
+    let mut current_ids: Vec<ObjectId> = current.iter().filter_map(|c| c.id).collect();
+    current_ids.sort();
+    let mut wanted_ids: Vec<ObjectId> = ordered_ids.to_vec();
+    wanted_ids.sort();
+    if current_ids != wanted_ids {
+        return Err(CategoryError::Other(anyhow::anyhow!(
+            "ordered_ids must contain exactly the supplier's current categories, no more and no fewer"
+        )));
+    }
+
+    let ranks = evenly_spaced_ranks(ordered_ids.len());
+
+    let mut delay = TXN_RETRY_BASE;
+    for attempt in 1..=TXN_MAX_ATTEMPTS {
+        let mut session = db.client.start_session().await.map_err(|e| CategoryError::Other(e.into()))?;
+        session
+            .start_transaction()
+            .await
+            .map_err(|e| CategoryError::Other(e.into()))?;
+
+        let write_result: mongodb::error::Result<()> = async {
+            for (id, rank) in ordered_ids.iter().zip(ranks.iter()) {
+                coll(db)
+                    .update_one(doc! { "_id": id }, doc! { "$set": { "rank": rank } })
+                    .session(&mut session)
+                    .await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = write_result {
+            let _ = session.abort_transaction().await;
+            if is_transient_transaction_error(&e) && attempt < TXN_MAX_ATTEMPTS {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(TXN_RETRY_CAP);
+                continue;
+            }
+            return Err(CategoryError::Other(e.into()));
+        }
+
+        match session.commit_transaction().await {
+            Ok(()) => return Ok(()),
+            Err(e) if is_transient_transaction_error(&e) && attempt < TXN_MAX_ATTEMPTS => {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(TXN_RETRY_CAP);
+            }
+            Err(e) => return Err(CategoryError::Other(e.into())),
+        }
+    }
+
+    // Unreachable in practice: the loop above always returns on its last
+    // attempt, win or lose.
+    Err(CategoryError::Other(anyhow::anyhow!("reorder: exhausted retries")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_between_head_and_tail() {
+        assert!(rank_between(None, None) < "z".to_string());
+        let appended = rank_between(Some("M"), None);
+        assert!(appended.as_str() > "M");
+        let prepended = rank_between(None, Some("M"));
+        assert!(prepended.as_str() < "M");
+    }
+
+    #[test]
+    fn rank_between_inserts_strictly_between_neighbors() {
+        let a = rank_between(None, None);
+        let b = rank_between(Some(&a), None);
+        assert!(a < b);
+        let mid = rank_between(Some(&a), Some(&b));
+        assert!(a < mid && mid < b);
+    }
+
+    #[test]
+    fn rank_between_handles_adjacent_digits_by_growing_longer() {
+        // "0" and "1" have no room between them at the first digit, so the
+        // result must grow an extra digit rather than collide with either.
+        let mid = rank_between(Some("0"), Some("1"));
+        assert!(mid.as_str() > "0" && mid.as_str() < "1");
+        assert!(mid.len() > 1);
+    }
+
+    #[test]
+    fn rank_between_many_consecutive_inserts_stay_ordered() {
+        let mut keys = vec![rank_between(None, None)];
+        for _ in 0..50 {
+            let last = keys.last().unwrap().clone();
+            keys.push(rank_between(Some(&last), None));
+        }
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+    }
+
+    #[test]
+    fn evenly_spaced_ranks_are_sorted_and_unique() {
+        let ranks = evenly_spaced_ranks(10);
+        assert_eq!(ranks.len(), 10);
+        let mut sorted = ranks.clone();
+        sorted.sort();
+        assert_eq!(ranks, sorted);
+        assert_eq!(ranks.iter().collect::<std::collections::BTreeSet<_>>().len(), 10);
+    }
+
+    #[test]
+    fn evenly_spaced_ranks_empty() {
+        assert!(evenly_spaced_ranks(0).is_empty());
+    }
+
+    /// Connects to a throwaway replica-set database for transaction tests
+    /// (transactions require one). Requires `MONGO_TEST_URI`; skipped
+    /// otherwise since this repo has no bundled MongoDB instance to test
+    /// against in CI.
+    async fn test_db() -> Option<Db> {
+        let uri = std::env::var("MONGO_TEST_URI").ok()?;
+        crate::db::connect(&uri, crate::config::EventTransport::ChangeStream, None, "categories-test")
+            .await
+            .ok()
+    }
+
+    #[tokio::test]
+    async fn concurrent_reorders_never_leave_duplicate_ranks() {
+        let Some(db) = test_db().await else { return };
+
+        let supplier_id = ObjectId::new();
+        coll(&db).delete_many(doc! { "supplier_id": supplier_id }).await.unwrap();
+        let mut ids = Vec::new();
+        for name in ["A", "B", "C", "D"] {
+            ids.push(create(&db, supplier_id, name).await.unwrap());
+        }
+
+        let forward = ids.clone();
+        let mut backward = ids.clone();
+        backward.reverse();
+
+        let (db_a, db_b) = (db.clone(), db.clone());
+        let (sid_a, sid_b) = (supplier_id, supplier_id);
+        let t1 = tokio::spawn(async move { reorder(&db_a, sid_a, &forward).await });
+        let t2 = tokio::spawn(async move { reorder(&db_b, sid_b, &backward).await });
+        let (r1, r2) = tokio::join!(t1, t2);
+        r1.unwrap().unwrap();
+        r2.unwrap().unwrap();
+
+        let after = list_by_supplier(&db, supplier_id).await.unwrap();
+        let unique_ranks: std::collections::BTreeSet<&str> = after.iter().map(|c| c.rank.as_str()).collect();
+        assert_eq!(unique_ranks.len(), after.len(), "no two categories should share a rank");
+    }
+
+    #[tokio::test]
+    async fn delete_with_strategy_empty_category_fast_path() {
+        let Some(db) = test_db().await else { return };
+        let supplier_id = ObjectId::new();
+        let cat_id = create(&db, supplier_id, "Empty").await.unwrap();
+
+        let report = delete_with_strategy(&db, cat_id, DeleteStrategy::Block).await.unwrap();
+        assert_eq!(report.affected_products, 0);
+        assert!(list_by_supplier(&db, supplier_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_with_strategy_block_refuses_when_referenced() {
+        let Some(db) = test_db().await else { return };
+        let supplier_id = ObjectId::new();
+        let cat_id = create(&db, supplier_id, "Drinks").await.unwrap();
+        let dish_id = crate::services::dishes::create(&db, supplier_id, "Cola", 250).await.unwrap();
+        crate::services::dishes::update_plain(&db, dish_id, "Cola", None, 250, vec![cat_id]).await.unwrap();
+
+        let err = delete_with_strategy(&db, cat_id, DeleteStrategy::Block).await.unwrap_err();
+        assert!(err.to_string().contains("still reference"));
+        // Category must survive a blocked delete.
+        assert!(list_by_supplier(&db, supplier_id).await.unwrap().iter().any(|c| c.id == Some(cat_id)));
+    }
+
+    #[tokio::test]
+    async fn delete_with_strategy_detach_clears_reference() {
+        let Some(db) = test_db().await else { return };
+        let supplier_id = ObjectId::new();
+        let cat_id = create(&db, supplier_id, "Drinks").await.unwrap();
+        let dish_id = crate::services::dishes::create(&db, supplier_id, "Cola", 250).await.unwrap();
+        crate::services::dishes::update_plain(&db, dish_id, "Cola", None, 250, vec![cat_id]).await.unwrap();
+
+        let report = delete_with_strategy(&db, cat_id, DeleteStrategy::Detach).await.unwrap();
+        assert_eq!(report.affected_products, 1);
+        let dish = crate::services::dishes::get(&db, dish_id).await.unwrap().unwrap();
+        assert!(!dish.categories.contains(&cat_id));
+    }
+
+    #[tokio::test]
+    async fn delete_with_strategy_reassign_repoints_reference() {
+        let Some(db) = test_db().await else { return };
+        let supplier_id = ObjectId::new();
+        let from_id = create(&db, supplier_id, "Drinks").await.unwrap();
+        let to_id = create(&db, supplier_id, "Beverages").await.unwrap();
+        let dish_id = crate::services::dishes::create(&db, supplier_id, "Cola", 250).await.unwrap();
+        crate::services::dishes::update_plain(&db, dish_id, "Cola", None, 250, vec![from_id]).await.unwrap();
+
+        let report = delete_with_strategy(&db, from_id, DeleteStrategy::Reassign(to_id)).await.unwrap();
+        assert_eq!(report.affected_products, 1);
+        let dish = crate::services::dishes::get(&db, dish_id).await.unwrap().unwrap();
+        assert!(dish.categories.contains(&to_id));
+        assert!(!dish.categories.contains(&from_id));
+    }
+}
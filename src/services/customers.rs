@@ -0,0 +1,41 @@
+// src/services/customers.rs
+use anyhow::Result;
+use futures_util::TryStreamExt;
+use mongodb::{bson::doc, Collection};
+
+use crate::db::Db;
+use crate::model::Customer;
+
+fn coll(db: &Db) -> Collection<Customer> {
+    db.collection::<Customer>("customers")
+}
+
+/// Every remembered customer, sorted by name — used for the order screen's
+/// "previously used names" dropdown on shared kiosks.
+pub async fn list(db: &Db) -> Result<Vec<Customer>> {
+    let mut cur = coll(db).find(doc! {}).await?;
+    let mut out = Vec::new();
+    while let Some(c) = cur.try_next().await? {
+        out.push(c);
+    }
+    out.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    Ok(out)
+}
+
+pub async fn get_by_client(db: &Db, client_id: &str) -> Result<Option<Customer>> {
+    Ok(coll(db).find_one(doc! { "client_id": client_id }).await?)
+}
+
+/// Creates or updates the profile for `client_id` with the given name/note.
+pub async fn upsert(db: &Db, client_id: &str, display_name: &str, note: Option<&str>) -> Result<()> {
+    let mut set = doc! { "display_name": display_name };
+    match note.filter(|n| !n.trim().is_empty()) {
+        Some(n) => { set.insert("note", n); }
+        None => { set.insert("note", mongodb::bson::Bson::Null); }
+    }
+    coll(db)
+        .update_one(doc! { "client_id": client_id }, doc! { "$set": set })
+        .upsert(true)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+// src/services/dish_repo.rs
+//
+// `DishRepo` abstracts dish/supplier data access behind a trait so UI/service
+// logic (order-building, pizza-size pricing) can be exercised in tests
+// without a live MongoDB. `MongoDishRepo` is the real backend used by the
+// app; `FakeDishRepo` is an in-memory stand-in for tests.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+
+use crate::db::Db;
+use crate::model::{Dish, DishInput, Extra, PizzaSize, Supplier, VariantGroup};
+use crate::services::{dishes, suppliers};
+
+#[async_trait]
+pub trait DishRepo: Send + Sync {
+    async fn list_by_supplier(&self, supplier_id: ObjectId) -> Result<Vec<Dish>>;
+    async fn get(&self, id: ObjectId) -> Result<Option<Dish>>;
+    async fn create_plain(
+        &self,
+        supplier_id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        categories: Vec<ObjectId>,
+    ) -> Result<ObjectId>;
+    async fn create_with_variants(&self, input: DishInput) -> Result<ObjectId>;
+    async fn delete(&self, id: ObjectId) -> Result<()>;
+    async fn update_plain(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        categories: Vec<ObjectId>,
+    ) -> Result<()>;
+    async fn update_pizza(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        sizes: Vec<PizzaSize>,
+        categories: Vec<ObjectId>,
+    ) -> Result<()>;
+    async fn update_with_variants(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        variant_groups: Vec<VariantGroup>,
+        extras: Vec<Extra>,
+        categories: Vec<ObjectId>,
+    ) -> Result<()>;
+
+    async fn list_suppliers(&self) -> Result<Vec<Supplier>>;
+    async fn get_supplier(&self, id: ObjectId) -> Result<Option<Supplier>>;
+}
+
+/// Real backend: delegates to the `services::dishes`/`services::suppliers` free functions.
+pub struct MongoDishRepo {
+    db: Db,
+}
+
+impl MongoDishRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl DishRepo for MongoDishRepo {
+    async fn list_by_supplier(&self, supplier_id: ObjectId) -> Result<Vec<Dish>> {
+        dishes::list_by_supplier(&self.db, supplier_id).await
+    }
+
+    async fn get(&self, id: ObjectId) -> Result<Option<Dish>> {
+        dishes::get(&self.db, id).await
+    }
+
+    async fn create_plain(
+        &self,
+        supplier_id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        categories: Vec<ObjectId>,
+    ) -> Result<ObjectId> {
+        dishes::create_plain(&self.db, supplier_id, name, number, price_cents, categories).await
+    }
+
+    async fn create_with_variants(&self, input: DishInput) -> Result<ObjectId> {
+        dishes::create_with_variants(&self.db, input).await
+    }
+
+    async fn delete(&self, id: ObjectId) -> Result<()> {
+        dishes::delete(&self.db, id).await
+    }
+
+    async fn update_plain(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        categories: Vec<ObjectId>,
+    ) -> Result<()> {
+        dishes::update_plain(&self.db, id, name, number, price_cents, categories).await
+    }
+
+    async fn update_pizza(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        sizes: Vec<PizzaSize>,
+        categories: Vec<ObjectId>,
+    ) -> Result<()> {
+        dishes::update_pizza(&self.db, id, name, number, sizes, categories).await
+    }
+
+    async fn update_with_variants(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        variant_groups: Vec<VariantGroup>,
+        extras: Vec<Extra>,
+        categories: Vec<ObjectId>,
+    ) -> Result<()> {
+        dishes::update_with_variants(&self.db, id, name, number, price_cents, variant_groups, extras, categories).await
+    }
+
+    async fn list_suppliers(&self) -> Result<Vec<Supplier>> {
+        suppliers::list(&self.db).await
+    }
+
+    async fn get_supplier(&self, id: ObjectId) -> Result<Option<Supplier>> {
+        suppliers::get_supplier(&self.db, id).await
+    }
+}
+
+/// In-memory stand-in for tests: holds dishes/suppliers in `Mutex<Vec<_>>`
+/// and assigns synthetic `ObjectId`s on insert instead of hitting MongoDB.
+#[derive(Default)]
+pub struct FakeDishRepo {
+    dishes: std::sync::Mutex<Vec<Dish>>,
+    suppliers: std::sync::Mutex<Vec<Supplier>>,
+}
+
+impl FakeDishRepo {
+    pub fn new(dishes: Vec<Dish>, suppliers: Vec<Supplier>) -> Self {
+        Self {
+            dishes: std::sync::Mutex::new(dishes),
+            suppliers: std::sync::Mutex::new(suppliers),
+        }
+    }
+}
+
+#[async_trait]
+impl DishRepo for FakeDishRepo {
+    async fn list_by_supplier(&self, supplier_id: ObjectId) -> Result<Vec<Dish>> {
+        Ok(self.dishes.lock().unwrap().iter().filter(|d| d.supplier_id == supplier_id).cloned().collect())
+    }
+
+    async fn get(&self, id: ObjectId) -> Result<Option<Dish>> {
+        Ok(self.dishes.lock().unwrap().iter().find(|d| d.id == Some(id)).cloned())
+    }
+
+    async fn create_plain(
+        &self,
+        supplier_id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        categories: Vec<ObjectId>,
+    ) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        self.dishes.lock().unwrap().push(Dish {
+            id: Some(id),
+            supplier_id,
+            name: name.to_string(),
+            price_cents,
+            tags: vec![],
+            number,
+            pizza_sizes: None,
+            variant_groups: None,
+            extras: None,
+            categories,
+        });
+        Ok(id)
+    }
+
+    async fn create_with_variants(&self, input: DishInput) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        self.dishes.lock().unwrap().push(Dish {
+            id: Some(id),
+            supplier_id: input.supplier_id,
+            name: input.name,
+            price_cents: input.price_cents.unwrap_or(0),
+            tags: input.tags,
+            number: input.number,
+            pizza_sizes: None,
+            variant_groups: input.variant_groups,
+            extras: input.extras,
+            categories: input.categories.unwrap_or_default(),
+        });
+        Ok(id)
+    }
+
+    async fn delete(&self, id: ObjectId) -> Result<()> {
+        self.dishes.lock().unwrap().retain(|d| d.id != Some(id));
+        Ok(())
+    }
+
+    async fn update_plain(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        categories: Vec<ObjectId>,
+    ) -> Result<()> {
+        let mut guard = self.dishes.lock().unwrap();
+        let d = guard.iter_mut().find(|d| d.id == Some(id)).ok_or_else(|| anyhow!("dish not found"))?;
+        d.name = name.to_string();
+        d.number = number;
+        d.price_cents = price_cents;
+        d.pizza_sizes = None;
+        d.tags = vec![];
+        d.categories = categories;
+        Ok(())
+    }
+
+    async fn update_pizza(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        sizes: Vec<PizzaSize>,
+        categories: Vec<ObjectId>,
+    ) -> Result<()> {
+        let mut guard = self.dishes.lock().unwrap();
+        let d = guard.iter_mut().find(|d| d.id == Some(id)).ok_or_else(|| anyhow!("dish not found"))?;
+        d.name = name.to_string();
+        d.number = number;
+        d.pizza_sizes = Some(sizes);
+        d.price_cents = 0;
+        d.tags = vec!["Pizza".to_string()];
+        d.categories = categories;
+        Ok(())
+    }
+
+    async fn update_with_variants(
+        &self,
+        id: ObjectId,
+        name: &str,
+        number: Option<String>,
+        price_cents: i64,
+        variant_groups: Vec<VariantGroup>,
+        extras: Vec<Extra>,
+        categories: Vec<ObjectId>,
+    ) -> Result<()> {
+        let mut guard = self.dishes.lock().unwrap();
+        let d = guard.iter_mut().find(|d| d.id == Some(id)).ok_or_else(|| anyhow!("dish not found"))?;
+        d.name = name.to_string();
+        d.number = number;
+        d.price_cents = price_cents;
+        d.pizza_sizes = None;
+        d.variant_groups = Some(variant_groups);
+        d.extras = Some(extras);
+        d.tags = vec![];
+        d.categories = categories;
+        Ok(())
+    }
+
+    async fn list_suppliers(&self) -> Result<Vec<Supplier>> {
+        Ok(self.suppliers.lock().unwrap().clone())
+    }
+
+    async fn get_supplier(&self, id: ObjectId) -> Result<Option<Supplier>> {
+        Ok(self.suppliers.lock().unwrap().iter().find(|s| s.id == Some(id)).cloned())
+    }
+}
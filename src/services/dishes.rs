@@ -4,7 +4,8 @@ use mongodb::bson::{self, doc, oid::ObjectId};
 use mongodb::Collection;
 
 use crate::db::Db;
-use crate::model::{Dish, DishInput, PizzaSize};
+use crate::model::{Dish, DishInput, Extra, PizzaSize, VariantGroup};
+use crate::services::events::Topic;
 
 fn coll(db: &Db) -> Collection<Dish> {
     db.collection::<Dish>("dishes")
@@ -37,9 +38,12 @@ pub async fn create(
         tags: vec![],
         number: None,
         pizza_sizes: None,
+        variant_groups: None,
+        extras: None,
         categories: Vec::new(),
     };
     let r = coll(db).insert_one(d).await?;
+    db.notify(Topic::Dishes).await;
     Ok(r.inserted_id.as_object_id().unwrap())
 }
 
@@ -59,9 +63,12 @@ pub async fn create_plain(
         tags: vec![],
         number,
         pizza_sizes: None,
+        variant_groups: None,
+        extras: None,
         categories,
     };
     let r = coll(db).insert_one(d).await?;
+    db.notify(Topic::Dishes).await;
     Ok(r.inserted_id.as_object_id().unwrap())
 }
 
@@ -74,17 +81,70 @@ pub async fn create_with_tags(db: &Db, input: DishInput) -> Result<ObjectId> {
         tags: input.tags,
         number: input.number,
         pizza_sizes: input.pizza_sizes,
+        variant_groups: input.variant_groups,
+        extras: input.extras,
         categories: input.categories.unwrap_or_default(),
     };
     let r = coll(db).insert_one(d).await?;
+    db.notify(Topic::Dishes).await;
+    Ok(r.inserted_id.as_object_id().unwrap())
+}
+
+/// Creates a dish modeled with the generic variant-group/extras system
+/// (e.g. drinks-with-sizes, menus-with-extras) rather than the legacy
+/// pizza-only `pizza_sizes` field.
+pub async fn create_with_variants(db: &Db, input: DishInput) -> Result<ObjectId> {
+    let d = Dish {
+        id: None,
+        supplier_id: input.supplier_id,
+        name: input.name,
+        price_cents: input.price_cents.unwrap_or(0),
+        tags: input.tags,
+        number: input.number,
+        pizza_sizes: None,
+        variant_groups: input.variant_groups,
+        extras: input.extras,
+        categories: input.categories.unwrap_or_default(),
+    };
+    let r = coll(db).insert_one(d).await?;
+    db.notify(Topic::Dishes).await;
     Ok(r.inserted_id.as_object_id().unwrap())
 }
 
 pub async fn delete(db: &Db, id: ObjectId) -> Result<()> {
     coll(db).delete_one(doc! { "_id": id }).await?;
+    db.notify(Topic::Dishes).await;
     Ok(())
 }
 
+/// How many dishes still list `category_id` among their `categories` —
+/// used by `services::categories::delete_with_strategy` to decide whether a
+/// category is safe to delete outright.
+pub async fn count_by_category(db: &Db, category_id: ObjectId) -> Result<u64> {
+    Ok(coll(db).count_documents(doc! { "categories": category_id }).await?)
+}
+
+/// Repoints every dish's reference to `from` at `to` within its
+/// `categories` array, in one `update_many`. Returns how many dishes were touched.
+pub async fn reassign_category(db: &Db, from: ObjectId, to: ObjectId) -> Result<u64> {
+    let r = coll(db)
+        .update_many(doc! { "categories": from }, doc! { "$set": { "categories.$[elem]": to } })
+        .array_filters(vec![doc! { "elem": from }])
+        .await?;
+    db.notify(Topic::Dishes).await;
+    Ok(r.modified_count)
+}
+
+/// Removes `category_id` from every dish's `categories` array, in one
+/// `update_many`. Returns how many dishes were touched.
+pub async fn detach_category(db: &Db, category_id: ObjectId) -> Result<u64> {
+    let r = coll(db)
+        .update_many(doc! { "categories": category_id }, doc! { "$pull": { "categories": category_id } })
+        .await?;
+    db.notify(Topic::Dishes).await;
+    Ok(r.modified_count)
+}
+
 pub async fn update_plain(
     db: &Db,
     id: ObjectId,
@@ -106,6 +166,7 @@ pub async fn update_plain(
             }},
         )
         .await?;
+    db.notify(Topic::Dishes).await;
     Ok(())
 }
 
@@ -130,5 +191,38 @@ pub async fn update_pizza(
             }},
         )
         .await?;
+    db.notify(Topic::Dishes).await;
+    Ok(())
+}
+
+/// Updates a dish's name/number/categories plus its variant groups and
+/// extras, clearing the legacy `pizza_sizes` field in favor of the generic
+/// system.
+pub async fn update_with_variants(
+    db: &Db,
+    id: ObjectId,
+    name: &str,
+    number: Option<String>,
+    price_cents: i64,
+    variant_groups: Vec<VariantGroup>,
+    extras: Vec<Extra>,
+    categories: Vec<ObjectId>,
+) -> Result<()> {
+    coll(db)
+        .update_one(
+            doc! { "_id": id },
+            doc! { "$set": {
+                "name": name,
+                "number": number,
+                "price_cents": price_cents,
+                "pizza_sizes": bson::Bson::Null,
+                "variant_groups": bson::to_bson(&variant_groups)?,
+                "extras": bson::to_bson(&extras)?,
+                "tags": [],
+                "categories": categories,
+            }},
+        )
+        .await?;
+    db.notify(Topic::Dishes).await;
     Ok(())
 }
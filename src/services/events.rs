@@ -0,0 +1,176 @@
+// src/services/events.rs
+//
+// Pluggable notifier for the four collections the UI watches for live
+// updates. `ChangeStreamBus` relies on MongoDB's own change streams, which
+// require a replica set; `MqttBus` is for standalone/shared clusters reached
+// through the agent, where mutations explicitly publish after a successful
+// write and every client subscribes to hear about them.
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use mongodb::Database;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::config::EventTransport;
+
+const MQTT_BACKOFF_BASE: Duration = Duration::from_millis(500);
+const MQTT_BACKOFF_CAP: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Settings,
+    Suppliers,
+    Dishes,
+    Orders,
+}
+
+impl Topic {
+    fn mqtt_topic(&self) -> &'static str {
+        match self {
+            Topic::Settings => "bestelldesk/settings/changed",
+            Topic::Suppliers => "bestelldesk/suppliers/changed",
+            Topic::Dishes => "bestelldesk/dishes/changed",
+            Topic::Orders => "bestelldesk/orders/changed",
+        }
+    }
+
+    fn from_mqtt_topic(s: &str) -> Option<Topic> {
+        match s {
+            "bestelldesk/settings/changed" => Some(Topic::Settings),
+            "bestelldesk/suppliers/changed" => Some(Topic::Suppliers),
+            "bestelldesk/dishes/changed" => Some(Topic::Dishes),
+            "bestelldesk/orders/changed" => Some(Topic::Orders),
+            _ => None,
+        }
+    }
+
+    fn to_app_msg(self) -> crate::AppMsg {
+        match self {
+            Topic::Settings => crate::AppMsg::SettingsChanged,
+            Topic::Suppliers => crate::AppMsg::SuppliersChanged,
+            Topic::Dishes => crate::AppMsg::DishesChanged,
+            Topic::Orders => crate::AppMsg::OrdersChanged,
+        }
+    }
+}
+
+#[async_trait]
+pub trait EventBus: Send + Sync {
+    /// Announces that `topic`'s collection just changed. No-op on
+    /// `ChangeStreamBus`, since the write itself is enough to trigger the
+    /// change stream; required on `MqttBus` so other clients hear about it.
+    async fn publish(&self, topic: Topic);
+
+    /// Spawns the background task(s) that forward external changes into `tx`
+    /// as `AppMsg`s for the UI's watcher channel.
+    fn spawn_listeners(self: Arc<Self>, rt: &Runtime, tx: UnboundedSender<crate::AppMsg>);
+}
+
+/// Builds the configured bus. For MQTT the broker URL is parsed here, so a
+/// malformed URL fails at startup; the broker connection itself is opened
+/// later, when the listener's event loop is polled.
+pub fn build(
+    transport: EventTransport,
+    broker_url: Option<&str>,
+    client_id: &str,
+    database: Database,
+) -> Result<Arc<dyn EventBus>> {
+    match transport {
+        EventTransport::ChangeStream => Ok(Arc::new(ChangeStreamBus::new(database))),
+        EventTransport::Mqtt => {
+            let url = broker_url.context("MQTT transport selected but no broker URL is configured")?;
+            Ok(Arc::new(MqttBus::new(url, client_id)?))
+        }
+    }
+}
+
+pub struct ChangeStreamBus {
+    database: Database,
+}
+
+impl ChangeStreamBus {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl EventBus for ChangeStreamBus {
+    async fn publish(&self, _topic: Topic) {}
+
+    fn spawn_listeners(self: Arc<Self>, rt: &Runtime, tx: UnboundedSender<crate::AppMsg>) {
+        let d = self.database.clone();
+        rt.spawn(crate::db::watch_settings(d.clone(), tx.clone()));
+        rt.spawn(crate::db::watch_suppliers(d.clone(), tx.clone()));
+        rt.spawn(crate::db::watch_dishes(d.clone(), tx.clone()));
+        rt.spawn(crate::db::watch_orders(d, tx));
+    }
+}
+
+pub struct MqttBus {
+    client: rumqttc::AsyncClient,
+    eventloop: Mutex<Option<rumqttc::EventLoop>>,
+}
+
+impl MqttBus {
+    pub fn new(broker_url: &str, client_id: &str) -> Result<Self> {
+        let (host, port) = parse_broker_url(broker_url)?;
+        let mut opts = rumqttc::MqttOptions::new(client_id, host, port);
+        opts.set_keep_alive(Duration::from_secs(30));
+        let (client, eventloop) = rumqttc::AsyncClient::new(opts, 16);
+        Ok(Self { client, eventloop: Mutex::new(Some(eventloop)) })
+    }
+}
+
+fn parse_broker_url(url: &str) -> Result<(String, u16)> {
+    let rest = url.trim_start_matches("mqtt://").trim_start_matches("tcp://");
+    let (host, port) = rest
+        .split_once(':')
+        .context("MQTT broker URL must be of the form host:port")?;
+    Ok((host.to_string(), port.parse().context("invalid MQTT broker port")?))
+}
+
+#[async_trait]
+impl EventBus for MqttBus {
+    async fn publish(&self, topic: Topic) {
+        let _ = self
+            .client
+            .publish(topic.mqtt_topic(), rumqttc::QoS::AtLeastOnce, false, b"changed".to_vec())
+            .await;
+    }
+
+    fn spawn_listeners(self: Arc<Self>, rt: &Runtime, tx: UnboundedSender<crate::AppMsg>) {
+        let Some(mut eventloop) = self.eventloop.lock().unwrap().take() else { return };
+        let client = self.client.clone();
+        rt.spawn(async move {
+            for t in [Topic::Settings, Topic::Suppliers, Topic::Dishes, Topic::Orders] {
+                let _ = client.subscribe(t.mqtt_topic(), rumqttc::QoS::AtLeastOnce).await;
+            }
+            // Mirrors `db::watch_collection`'s reconnect behavior: keep
+            // polling through broker hiccups with exponential backoff
+            // (capped, reset after a successful event) instead of letting
+            // one error permanently kill live updates for the session.
+            let mut delay = MQTT_BACKOFF_BASE;
+            loop {
+                match eventloop.poll().await {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(p))) => {
+                        if let Some(topic) = Topic::from_mqtt_topic(&p.topic) {
+                            let _ = tx.send(topic.to_app_msg());
+                        }
+                        delay = MQTT_BACKOFF_BASE;
+                    }
+                    Ok(_) => {
+                        delay = MQTT_BACKOFF_BASE;
+                    }
+                    Err(_) => {
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(MQTT_BACKOFF_CAP);
+                    }
+                }
+            }
+        });
+    }
+}
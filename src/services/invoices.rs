@@ -0,0 +1,234 @@
+// src/services/invoices.rs
+//
+// Turns one or more `Order` documents into an `Invoice` — a receipt a
+// treasurer can hand off for reimbursement. Never persisted to Mongo; built
+// on demand and handed straight to a renderer, the same way `export.rs`
+// builds a menu document on demand.
+
+use anyhow::{ensure, Context, Result};
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::db::Db;
+use crate::model::{Invoice, InvoiceCustomerTotal, InvoiceLine, Order};
+use crate::services::{orders, suppliers};
+
+fn eur(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.abs();
+    format!("{sign}€{}.{}", abs / 100, format!("{:02}", abs % 100))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn lines_for(order: &Order) -> Vec<InvoiceLine> {
+    order
+        .items
+        .iter()
+        .map(|i| InvoiceLine {
+            name: i.name.clone(),
+            variant: i.variant.clone(),
+            qty: i.qty,
+            unit_price_cents: i.unit_price_cents,
+            line_total_cents: i.line_total_cents,
+        })
+        .collect()
+}
+
+/// Builds a single-order receipt, billed to the order's own supplier.
+pub async fn build_for_order(db: &Db, order_id: ObjectId) -> Result<Invoice> {
+    let order = orders::get(db, order_id).await?.context("order not found")?;
+    let supplier = suppliers::get_supplier(db, order.supplier_id)
+        .await?
+        .context("supplier not found")?;
+
+    Ok(Invoice {
+        invoice_number: order.order_code.clone(),
+        issue_date: order.created_at,
+        supplier_name: supplier.name,
+        lines: lines_for(&order),
+        items_total_cents: order.items_total_cents,
+        delivery_fee_cents: order.delivery_fee_cents,
+        grand_total_cents: order.grand_total_cents,
+        customer_totals: vec![InvoiceCustomerTotal {
+            customer_name: order.customer_name,
+            order_code: order.order_code,
+            subtotal_cents: order.grand_total_cents,
+        }],
+    })
+}
+
+/// Aggregates every order of a supplier's delivery round (`from`..`to`,
+/// `created_at` inclusive) into one receipt, with a per-customer sub-total
+/// so a treasurer can see who owes what within the round.
+pub async fn build_for_supplier_run(
+    db: &Db,
+    supplier_id: ObjectId,
+    from: DateTime,
+    to: DateTime,
+) -> Result<Invoice> {
+    let supplier = suppliers::get_supplier(db, supplier_id)
+        .await?
+        .context("supplier not found")?;
+
+    let run: Vec<Order> = orders::list_by_supplier(db, supplier_id)
+        .await?
+        .into_iter()
+        .filter(|o| o.created_at >= from && o.created_at <= to)
+        .collect();
+    ensure!(!run.is_empty(), "no orders in that date range");
+
+    let invoice_number = format!("RUN-{}", run[0].order_code);
+
+    let mut lines = Vec::new();
+    let mut customer_totals = Vec::new();
+    let mut items_total_cents = 0;
+    let mut delivery_fee_cents = 0;
+    for o in &run {
+        lines.extend(lines_for(o));
+        items_total_cents += o.items_total_cents;
+        delivery_fee_cents += o.delivery_fee_cents;
+        customer_totals.push(InvoiceCustomerTotal {
+            customer_name: o.customer_name.clone(),
+            order_code: o.order_code.clone(),
+            subtotal_cents: o.grand_total_cents,
+        });
+    }
+
+    Ok(Invoice {
+        invoice_number,
+        issue_date: DateTime::now(),
+        supplier_name: supplier.name,
+        lines,
+        items_total_cents,
+        delivery_fee_cents,
+        grand_total_cents: items_total_cents + delivery_fee_cents,
+        customer_totals,
+    })
+}
+
+/// Plain-text rendering, suitable for a terminal, an email body, or a quick copy/paste.
+pub fn render_text(inv: &Invoice) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Rechnung {}\n", inv.invoice_number));
+    out.push_str(&format!("Lieferant: {}\n", inv.supplier_name));
+    out.push_str(&format!("Datum: {}\n\n", inv.issue_date));
+
+    for l in &inv.lines {
+        let variant = l.variant.as_deref().map(|v| format!(" ({v})")).unwrap_or_default();
+        out.push_str(&format!(
+            "{:>3}x {}{}  {} = {}\n",
+            l.qty,
+            l.name,
+            variant,
+            eur(l.unit_price_cents),
+            eur(l.line_total_cents)
+        ));
+    }
+
+    out.push_str(&format!("\nZwischensumme: {}\n", eur(inv.items_total_cents)));
+    out.push_str(&format!("Liefergebühr: {}\n", eur(inv.delivery_fee_cents)));
+    out.push_str(&format!("Gesamt: {}\n", eur(inv.grand_total_cents)));
+
+    if inv.customer_totals.len() > 1 {
+        out.push_str("\nPro Kunde:\n");
+        for c in &inv.customer_totals {
+            out.push_str(&format!("  {} ({})  {}\n", c.customer_name, c.order_code, eur(c.subtotal_cents)));
+        }
+    }
+
+    out
+}
+
+/// Self-contained, printable HTML layout — print-to-PDF from a browser gives
+/// the PDF artifact, the same way `export.rs::menu_html` stands in for a menu PDF.
+pub fn render_html(inv: &Invoice) -> String {
+    let rows = inv
+        .lines
+        .iter()
+        .map(|l| {
+            let variant = l.variant.as_deref().map(|v| format!(" ({})", escape_html(v))).unwrap_or_default();
+            format!(
+                "<tr><td>{}x</td><td>{}{}</td><td>{}</td><td>{}</td></tr>",
+                l.qty,
+                escape_html(&l.name),
+                variant,
+                eur(l.unit_price_cents),
+                eur(l.line_total_cents)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let customer_section = if inv.customer_totals.len() > 1 {
+        let rows = inv
+            .customer_totals
+            .iter()
+            .map(|c| {
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(&c.customer_name),
+                    escape_html(&c.order_code),
+                    eur(c.subtotal_cents)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "<section><h2>Pro Kunde</h2><table class=\"totals\"><tr><th>Kunde</th><th>Bestellung</th><th>Summe</th></tr>{rows}</table></section>"
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+<meta charset="utf-8">
+<title>Rechnung {number} — {supplier}</title>
+<style>
+  body {{ font-family: sans-serif; max-width: 700px; margin: 2em auto; color: #222; }}
+  header {{ border-bottom: 2px solid #333; margin-bottom: 1.5em; padding-bottom: 0.5em; }}
+  h1 {{ margin-bottom: 0.2em; }}
+  .meta {{ color: #666; font-size: 0.9em; }}
+  table {{ width: 100%; border-collapse: collapse; margin-bottom: 1em; }}
+  table td, table th {{ padding: 0.3em 0.6em; border-bottom: 1px dotted #ccc; text-align: left; }}
+  .totals td, .totals th {{ text-align: right; }}
+  .totals td:first-child, .totals th:first-child {{ text-align: left; }}
+  .grand {{ font-weight: bold; font-size: 1.1em; }}
+  @media print {{ body {{ margin: 0; }} }}
+</style>
+</head>
+<body>
+<header>
+  <h1>Rechnung {number}</h1>
+  <div class="meta">Lieferant: {supplier} &middot; Datum: {date}</div>
+</header>
+<section>
+<table>
+<tr><th>Menge</th><th>Artikel</th><th>Einzelpreis</th><th>Summe</th></tr>
+{rows}
+</table>
+<table class="totals">
+<tr><td>Zwischensumme</td><td></td><td>{items_total}</td></tr>
+<tr><td>Liefergebühr</td><td></td><td>{fee}</td></tr>
+<tr class="grand"><td>Gesamt</td><td></td><td>{grand_total}</td></tr>
+</table>
+</section>
+{customer_section}
+</body>
+</html>
+"#,
+        number = escape_html(&inv.invoice_number),
+        supplier = escape_html(&inv.supplier_name),
+        date = inv.issue_date,
+        items_total = eur(inv.items_total_cents),
+        fee = eur(inv.delivery_fee_cents),
+        grand_total = eur(inv.grand_total_cents),
+    )
+}
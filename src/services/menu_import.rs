@@ -0,0 +1,186 @@
+// src/services/menu_import.rs
+//
+// Read-only bulk menu import: scans a folder of `.json`/`.csv` files and
+// bulk-inserts them as dishes for one supplier, auto-creating any missing
+// categories by name. Source files are only ever read, never written back.
+
+use anyhow::{Context, Result};
+use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::db::Db;
+use crate::model::{DishInput, VariantGroup};
+use crate::services::{categories, dishes};
+
+/// One dish as described in a menu file, before resolving category names to ids.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct MenuRecord {
+    name: String,
+    #[serde(default)]
+    number: Option<String>,
+    #[serde(default)]
+    price_cents: Option<i64>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    variant_groups: Option<Vec<VariantGroup>>,
+}
+
+pub struct ImportReport {
+    pub inserted: usize,
+    pub skipped_duplicate: usize,
+    /// (file name, error message) for files that failed to parse or whose
+    /// records were invalid; the rest of the folder still gets imported.
+    pub errors: Vec<(String, String)>,
+}
+
+/// Parses every `.json`/`.csv` file in `dir` into dishes for `supplier_id`,
+/// auto-creating missing categories and skipping dishes that already exist
+/// (matched by number+name) so re-running the import is idempotent.
+pub async fn import_folder(db: &Db, supplier_id: ObjectId, dir: &str) -> Result<ImportReport> {
+    let mut report = ImportReport { inserted: 0, skipped_duplicate: 0, errors: Vec::new() };
+
+    let mut cat_ids: HashMap<String, ObjectId> = categories::list_by_supplier(db, supplier_id)
+        .await?
+        .into_iter()
+        .filter_map(|c| c.id.map(|id| (c.name, id)))
+        .collect();
+
+    let existing: std::collections::HashSet<(String, String)> = dishes::list_by_supplier(db, supplier_id)
+        .await?
+        .into_iter()
+        .map(|d| (d.number.unwrap_or_default(), d.name))
+        .collect();
+    let mut seen = existing;
+
+    let entries = std::fs::read_dir(dir).with_context(|| format!("read dir {dir}"))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("?").to_string();
+
+        let records = match ext.as_str() {
+            "json" => parse_json_file(&path),
+            "csv" => parse_csv_file(&path),
+            _ => continue,
+        };
+
+        let records = match records {
+            Ok(r) => r,
+            Err(e) => {
+                report.errors.push((file_name, e.to_string()));
+                continue;
+            }
+        };
+
+        for rec in records {
+            let key = (rec.number.clone().unwrap_or_default(), rec.name.clone());
+            if seen.contains(&key) {
+                report.skipped_duplicate += 1;
+                continue;
+            }
+
+            let mut category_ids = Vec::new();
+            for cat_name in &rec.categories {
+                if let Some(id) = cat_ids.get(cat_name) {
+                    category_ids.push(*id);
+                } else {
+                    match categories::create(db, supplier_id, cat_name).await {
+                        Ok(id) => {
+                            cat_ids.insert(cat_name.clone(), id);
+                            category_ids.push(id);
+                        }
+                        Err(e) => {
+                            report.errors.push((file_name.clone(), format!("category '{cat_name}': {e}")));
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            let res = if let Some(groups) = rec.variant_groups.clone().filter(|g| !g.is_empty()) {
+                let input = DishInput {
+                    supplier_id,
+                    name: rec.name.clone(),
+                    price_cents: None,
+                    tags: vec![],
+                    number: rec.number.clone(),
+                    pizza_sizes: None,
+                    variant_groups: Some(groups),
+                    extras: None,
+                    categories: Some(category_ids),
+                };
+                dishes::create_with_variants(db, input).await
+            } else {
+                dishes::create_plain(
+                    db,
+                    supplier_id,
+                    &rec.name,
+                    rec.number.clone(),
+                    rec.price_cents.unwrap_or(0),
+                    category_ids,
+                )
+                .await
+            };
+
+            match res {
+                Ok(_) => {
+                    seen.insert(key);
+                    report.inserted += 1;
+                }
+                Err(e) => report.errors.push((file_name.clone(), format!("insert '{}': {e}", rec.name))),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn parse_json_file(path: &std::path::Path) -> Result<Vec<MenuRecord>> {
+    let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_slice(&bytes).with_context(|| format!("parse JSON {}", path.display()))
+}
+
+/// Minimal CSV parser for `number,name,price_cents,categories` rows, where
+/// `categories` is a `;`-separated list. No variant groups in this format —
+/// use `.json` for dishes with sizes.
+fn parse_csv_file(path: &std::path::Path) -> Result<Vec<MenuRecord>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || i == 0 && line.to_lowercase().starts_with("number,name") {
+            continue;
+        }
+        let cols: Vec<&str> = line.split(',').collect();
+        anyhow::ensure!(cols.len() >= 2, "line {}: expected at least number,name", i + 1);
+
+        let number = cols[0].trim();
+        let name = cols[1].trim();
+        anyhow::ensure!(!name.is_empty(), "line {}: empty name", i + 1);
+
+        let price_cents = cols.get(2).and_then(|s| s.trim().parse::<i64>().ok());
+        let categories = cols
+            .get(3)
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.split(';').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect())
+            .unwrap_or_default();
+
+        out.push(MenuRecord {
+            name: name.to_string(),
+            number: if number.is_empty() { None } else { Some(number.to_string()) },
+            price_cents,
+            categories,
+            variant_groups: None,
+        });
+    }
+    Ok(out)
+}
+
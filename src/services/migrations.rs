@@ -0,0 +1,176 @@
+// src/services/migrations.rs
+//
+// Schema-evolution registry for the raw collections the rest of the crate
+// touches directly (e.g. `categories`' rank-key format). An `admin_migrations`
+// collection holds a single document with the current `revision`; at
+// startup `run` applies every registered migration above that revision, in
+// order, bumping the stored revision after each one succeeds — so a boot
+// that fails partway through resumes from where it left off next time.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::{Context, Result};
+use mongodb::bson::doc;
+use mongodb::Collection;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RevisionDoc {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<mongodb::bson::oid::ObjectId>,
+    revision: i64,
+}
+
+/// A single migration step: takes the schema from revision `i` to `i + 1`.
+/// Boxed-future `fn` pointers (rather than an `async fn` item) so steps of
+/// different bodies can live together in one `Vec`.
+type MigrationFn = for<'a> fn(&'a Db) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// Registered migrations, in order; the step at index `i` advances the
+/// schema from revision `i` to revision `i + 1`. Add new migrations to the
+/// end — never reorder or remove a shipped one, since `revision` only
+/// records a count applied, not which migrations ran.
+fn migrations() -> Vec<MigrationFn> {
+    vec![backfill_category_ranks, ensure_category_indexes, ensure_user_indexes]
+}
+
+fn coll(db: &Db) -> Collection<RevisionDoc> {
+    db.collection::<RevisionDoc>("admin_migrations")
+}
+
+async fn current_revision(db: &Db) -> Result<i64> {
+    Ok(coll(db).find_one(doc! {}).await?.map(|d| d.revision).unwrap_or(0))
+}
+
+async fn set_revision(db: &Db, revision: i64) -> Result<()> {
+    coll(db)
+        .update_one(doc! {}, doc! { "$set": { "revision": revision } })
+        .upsert(true)
+        .await?;
+    Ok(())
+}
+
+/// Applies every registered migration above the stored revision. Aborts
+/// boot on the first failure, reporting which revision it was trying to
+/// reach so an operator can tell how far the schema got.
+pub async fn run(db: &Db) -> Result<()> {
+    run_steps(db, &migrations()).await
+}
+
+async fn run_steps(db: &Db, steps: &[MigrationFn]) -> Result<()> {
+    let mut revision = current_revision(db).await.context("read schema revision")?;
+    while (revision as usize) < steps.len() {
+        let target = revision + 1;
+        steps[revision as usize](db)
+            .await
+            .with_context(|| format!("migration to revision {target} failed"))?;
+        set_revision(db, target).await.context("persist schema revision")?;
+        revision = target;
+    }
+    Ok(())
+}
+
+/// Revision 1: backfills `rank` on any `categories` document still carrying
+/// only the pre-chunk5-1 integer `position`, assigning evenly spaced rank
+/// keys in `position` order (name as tie-break) and dropping `position`.
+fn backfill_category_ranks(db: &Db) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+    Box::pin(async move {
+        use futures_util::TryStreamExt;
+        use mongodb::bson::{oid::ObjectId, Document};
+
+        let coll = db.collection::<Document>("categories");
+        let mut cur = coll
+            .find(doc! { "position": { "$exists": true }, "rank": { "$exists": false } })
+            .await?;
+        let mut legacy: Vec<(ObjectId, i64, String)> = Vec::new();
+        while let Some(d) = cur.try_next().await? {
+            let id = d.get_object_id("_id")?;
+            let position = d.get_i64("position").unwrap_or(0);
+            let name = d.get_str("name").unwrap_or_default().to_string();
+            legacy.push((id, position, name));
+        }
+        legacy.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.2.cmp(&b.2)));
+
+        let ranks = super::categories::evenly_spaced_ranks(legacy.len());
+        for ((id, _, _), rank) in legacy.iter().zip(ranks) {
+            coll.update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "rank": rank }, "$unset": { "position": "" } },
+            )
+            .await?;
+        }
+        Ok(())
+    })
+}
+
+/// Revision 2: builds the unique `{ supplier_id, name }` index (and the
+/// supporting `{ supplier_id, rank }` one) that `categories::create`/`rename`
+/// rely on to detect duplicate names.
+fn ensure_category_indexes(db: &Db) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+    Box::pin(super::categories::ensure_indexes(db))
+}
+
+/// Revision 3: builds the unique `username` index that `users::create_user`
+/// relies on to reject duplicates via the insert's write error instead of a
+/// racy check-then-insert.
+fn ensure_user_indexes(db: &Db) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+    Box::pin(super::users::ensure_indexes(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    /// Connects to a throwaway database for migration tests. Requires
+    /// `MONGO_TEST_URI` (pointing at a scratch database, e.g.
+    /// `mongodb://localhost:27017/bestelldesk_migrations_test`); skipped
+    /// otherwise since this repo has no bundled MongoDB instance to test
+    /// against in CI.
+    async fn test_db() -> Option<Db> {
+        let uri = std::env::var("MONGO_TEST_URI").ok()?;
+        crate::db::connect(&uri, crate::config::EventTransport::ChangeStream, None, "migrations-test")
+            .await
+            .ok()
+    }
+
+    static STEP_ONE_CALLS: AtomicI64 = AtomicI64::new(0);
+    static STEP_TWO_CALLS: AtomicI64 = AtomicI64::new(0);
+
+    fn step_one(_db: &Db) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            STEP_ONE_CALLS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+
+    fn step_two(_db: &Db) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            STEP_TWO_CALLS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn run_steps_advances_revision_exactly_once_per_migration() {
+        let Some(db) = test_db().await else { return };
+        coll(&db).delete_many(doc! {}).await.unwrap();
+
+        assert_eq!(current_revision(&db).await.unwrap(), 0);
+
+        run_steps(&db, &[step_one, step_two]).await.unwrap();
+
+        assert_eq!(current_revision(&db).await.unwrap(), 2);
+        assert_eq!(STEP_ONE_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(STEP_TWO_CALLS.load(Ordering::SeqCst), 1);
+
+        // Re-running against the same (now up to date) database must not
+        // re-apply either step.
+        run_steps(&db, &[step_one, step_two]).await.unwrap();
+        assert_eq!(STEP_ONE_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(STEP_TWO_CALLS.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod agent_client;
+pub mod backup;
+pub mod backup_target;
+pub mod categories;
+pub mod customers;
+pub mod dish_repo;
+pub mod dishes;
+pub mod events;
+pub mod invoices;
+pub mod menu_import;
+pub mod migrations;
+pub mod orders;
+pub mod settings;
+pub mod stats;
+pub mod suppliers;
+pub mod updater;
+pub mod users;
@@ -5,11 +5,16 @@ use mongodb::Collection;
 
 use crate::db::Db;
 use crate::model::Order;
+use crate::services::events::Topic;
 
 fn coll(db: &Db) -> Collection<Order> {
     db.collection::<Order>("orders")
 }
 
+pub async fn get(db: &Db, id: ObjectId) -> Result<Option<Order>> {
+    Ok(coll(db).find_one(doc! { "_id": id }).await?)
+}
+
 pub async fn list_by_supplier(db: &Db, supplier_id: ObjectId) -> Result<Vec<Order>> {
     let mut cur = coll(db)
         .find(doc! { "supplier_id": supplier_id })
@@ -30,11 +35,13 @@ pub async fn set_paid_cents(db: &Db, id: ObjectId, paid_cents: i64, completed: b
             doc! { "$set": { "paid_cents": paid_cents, "completed": completed } },
         )
         .await?;
+    db.notify(Topic::Orders).await;
     Ok(())
 }
 
 pub async fn delete(db: &Db, id: ObjectId) -> Result<()> {
     coll(db).delete_one(doc! { "_id": id }).await?;
+    db.notify(Topic::Orders).await;
     Ok(())
 }
 
@@ -85,5 +92,6 @@ pub async fn create_with_notes(
     };
 
     let r = db.collection::<mongodb::bson::Document>("orders").insert_one(order_doc).await?;
+    db.notify(Topic::Orders).await;
     Ok(r.inserted_id.as_object_id().unwrap())
 }
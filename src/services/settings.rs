@@ -20,3 +20,28 @@ pub async fn set_active_supplier(db: &Db, sid: ObjectId) -> Result<()> {
 pub async fn get_active_supplier_id(db: &Db) -> Result<Option<ObjectId>> {
     Ok(get(db).await?.and_then(|s| s.active_supplier_id))
 }
+
+pub async fn set_s3_backup(db: &Db, cfg: Option<crate::model::S3BackupConfig>) -> Result<()> {
+    let coll: Collection<AppSettings> = db.db.collection("settings");
+    let bson = mongodb::bson::to_bson(&cfg)?;
+    coll.update_one(doc! {}, doc! { "$set": { "s3_backup": bson } })
+        .upsert(true)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_s3_backup(db: &Db) -> Result<Option<crate::model::S3BackupConfig>> {
+    Ok(get(db).await?.and_then(|s| s.s3_backup))
+}
+
+pub async fn set_theme_name(db: &Db, name: &str) -> Result<()> {
+    let coll: Collection<AppSettings> = db.db.collection("settings");
+    coll.update_one(doc! {}, doc! { "$set": { "theme_name": name } })
+        .upsert(true)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_theme_name(db: &Db) -> Result<Option<String>> {
+    Ok(get(db).await?.and_then(|s| s.theme_name))
+}
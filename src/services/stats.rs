@@ -0,0 +1,155 @@
+// src/services/stats.rs
+//
+// MongoDB aggregation pipelines over `orders` for the admin stats panel:
+// revenue/order counts per supplier, dish popularity rankings, and spend
+// trends over time.
+
+use anyhow::{Context, Result};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, from_document, oid::ObjectId, DateTime, Document};
+use mongodb::Collection;
+use serde::Deserialize;
+
+use crate::db::Db;
+use crate::model::Dish;
+use crate::services::dishes;
+
+fn coll(db: &Db) -> Collection<Document> {
+    db.db.collection::<Document>("orders")
+}
+
+#[derive(Debug, Clone)]
+pub struct SupplierTotal {
+    pub supplier_id: ObjectId,
+    pub orders: i64,
+    pub revenue_cents: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SupplierTotalRow {
+    #[serde(rename = "_id")]
+    supplier_id: ObjectId,
+    orders: i64,
+    revenue_cents: i64,
+}
+
+/// Revenue and order count per supplier for orders created within
+/// `[from, to)`, richest supplier first.
+pub async fn supplier_totals(db: &Db, from: DateTime, to: DateTime) -> Result<Vec<SupplierTotal>> {
+    let pipeline = vec![
+        doc! { "$match": { "created_at": { "$gte": from, "$lt": to } } },
+        doc! { "$group": {
+            "_id": "$supplier_id",
+            "orders": { "$sum": 1 },
+            "revenue_cents": { "$sum": "$grand_total_cents" },
+        }},
+        doc! { "$sort": { "revenue_cents": -1 } },
+    ];
+
+    let mut cur = coll(db).aggregate(pipeline).await?;
+    let mut out = Vec::new();
+    while let Some(d) = cur.try_next().await? {
+        let row: SupplierTotalRow = from_document(d).context("parse supplier_totals row")?;
+        out.push(SupplierTotal {
+            supplier_id: row.supplier_id,
+            orders: row.orders,
+            revenue_cents: row.revenue_cents,
+        });
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Deserialize)]
+struct DishPopularityRow {
+    #[serde(rename = "_id")]
+    dish_id: ObjectId,
+    count: i64,
+    revenue_cents: i64,
+}
+
+/// Ranks dishes ordered for `supplier_id` by quantity sold, unwinding each
+/// order's line items. Dishes since deleted are skipped.
+pub async fn dish_popularity(db: &Db, supplier_id: ObjectId) -> Result<Vec<(Dish, i64, i64)>> {
+    let pipeline = vec![
+        doc! { "$match": { "supplier_id": supplier_id } },
+        doc! { "$unwind": "$items" },
+        doc! { "$group": {
+            "_id": "$items.dish_id",
+            "count": { "$sum": "$items.qty" },
+            "revenue_cents": { "$sum": "$items.line_total_cents" },
+        }},
+        doc! { "$sort": { "count": -1 } },
+    ];
+
+    let mut cur = coll(db).aggregate(pipeline).await?;
+    let mut out = Vec::new();
+    while let Some(d) = cur.try_next().await? {
+        let row: DishPopularityRow = from_document(d).context("parse dish_popularity row")?;
+        if let Some(dish) = dishes::get(db, row.dish_id).await? {
+            out.push((dish, row.count, row.revenue_cents));
+        }
+    }
+    Ok(out)
+}
+
+/// Granularity for `spend_over_time`'s `$dateTrunc` grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    Week,
+}
+
+impl Bucket {
+    fn unit(self) -> &'static str {
+        match self {
+            Bucket::Day => "day",
+            Bucket::Week => "week",
+        }
+    }
+}
+
+impl Default for Bucket {
+    fn default() -> Self {
+        Bucket::Day
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpendBucket {
+    pub bucket_start: DateTime,
+    pub orders: i64,
+    pub revenue_cents: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpendBucketRow {
+    #[serde(rename = "_id")]
+    bucket_start: DateTime,
+    orders: i64,
+    revenue_cents: i64,
+}
+
+/// Revenue/order counts across all suppliers, grouped into day- or
+/// week-sized buckets via `$dateTrunc`, oldest bucket first.
+pub async fn spend_over_time(db: &Db, bucket: Bucket) -> Result<Vec<SpendBucket>> {
+    let pipeline = vec![
+        doc! { "$group": {
+            "_id": { "$dateTrunc": { "date": "$created_at", "unit": bucket.unit() } },
+            "orders": { "$sum": 1 },
+            "revenue_cents": { "$sum": "$grand_total_cents" },
+        }},
+        doc! { "$sort": { "_id": 1 } },
+    ];
+
+    let mut cur = coll(db).aggregate(pipeline).await?;
+    let mut out = Vec::new();
+    while let Some(d) = cur.try_next().await? {
+        let row: SpendBucketRow = from_document(d).context("parse spend_over_time row")?;
+        out.push(SpendBucket {
+            bucket_start: row.bucket_start,
+            orders: row.orders,
+            revenue_cents: row.revenue_cents,
+        });
+    }
+    Ok(out)
+}
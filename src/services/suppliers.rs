@@ -5,7 +5,7 @@ use mongodb::{
     Collection,
 };
 
-use crate::{db::Db, model::Supplier};
+use crate::{db::Db, model::Supplier, services::events::Topic};
 
 pub async fn list(db: &Db) -> Result<Vec<Supplier>> {
     let coll: Collection<Supplier> = db.db.collection("suppliers");
@@ -31,6 +31,7 @@ pub async fn create(db: &Db, name: &str, fee_cents: i64) -> Result<ObjectId> {
         is_active: false, // DB braucht Feld, aber UI zeigt es nicht
     };
     let r = coll.insert_one(ins).await?;
+    db.notify(Topic::Suppliers).await;
     Ok(r.inserted_id.as_object_id().unwrap())
 }
 
@@ -49,11 +50,13 @@ pub async fn update(
         }},
     )
     .await?;
+    db.notify(Topic::Suppliers).await;
     Ok(())
 }
 
 pub async fn delete(db: &Db, id: ObjectId) -> Result<()> {
     let coll: Collection<Supplier> = db.db.collection("suppliers");
     coll.delete_one(doc! { "_id": id }).await?;
+    db.notify(Topic::Suppliers).await;
     Ok(())
 }
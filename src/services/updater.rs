@@ -1,4 +1,5 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use flate2::read::GzDecoder;
 use semver::Version;
 use serde::Deserialize;
@@ -8,12 +9,23 @@ use std::{
 };
 use tar::Archive;
 
+use crate::config::UpdateChannel;
+
+/// Ed25519 public key (hex-encoded, 32 bytes) used to verify detached
+/// signatures on release archives. The matching private key lives with the
+/// release pipeline and never touches this repo.
+const UPDATE_PUBKEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511";
+
 #[derive(Debug, Clone)]
 pub struct UpdateInfo {
     pub tag: String,
     pub notes: String,
     pub asset_url: String,
     pub asset_name: String,
+    /// Download URL for a companion `<asset>.sha256` checksum asset, if the release published one.
+    pub checksum_url: Option<String>,
+    /// Download URL for the companion `<asset>.sig` Ed25519 detached signature.
+    pub sig_url: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -21,6 +33,8 @@ struct Release {
     tag_name: String,
     body: Option<String>,
     assets: Vec<Asset>,
+    #[serde(default)]
+    prerelease: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -89,8 +103,11 @@ pub async fn check_latest(
     owner: &str,
     repo: &str,
     current_ver: &str,
+    channel: UpdateChannel,
 ) -> Result<Option<UpdateInfo>> {
-    let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/latest");
+    // The `/releases` list (newest first) lets the Nightly channel also
+    // consider prereleases; Stable just filters them back out.
+    let url = format!("https://api.github.com/repos/{owner}/{repo}/releases");
     let client = reqwest::Client::new();
 
     let resp = client
@@ -102,7 +119,14 @@ pub async fn check_latest(
         .await?
         .error_for_status()?;
 
-    let rel: Release = resp.json().await?;
+    let rels: Vec<Release> = resp.json().await?;
+    let rel = match rels
+        .into_iter()
+        .find(|r| channel == UpdateChannel::Nightly || !r.prerelease)
+    {
+        Some(r) => r,
+        None => return Ok(None),
+    };
 
     fn normalize_tag(s: &str) -> &str {
         s.strip_prefix('v').unwrap_or(s)
@@ -118,17 +142,73 @@ pub async fn check_latest(
     let needle = format!("{arch}-{target_os_tag}");
 
     if let Some(a) = rel.assets.iter().find(|a| a.name.contains(&needle)) {
+        let checksum_name = format!("{}.sha256", a.name);
+        let checksum_url = rel
+            .assets
+            .iter()
+            .find(|c| c.name == checksum_name)
+            .map(|c| c.browser_download_url.clone());
+
+        let sig_name = format!("{}.sig", a.name);
+        let sig_url = rel
+            .assets
+            .iter()
+            .find(|c| c.name == sig_name)
+            .map(|c| c.browser_download_url.clone())
+            .with_context(|| format!("Release {} is missing a {sig_name} signature asset", rel.tag_name))?;
+
         Ok(Some(UpdateInfo {
             tag: rel.tag_name,
             notes: rel.body.unwrap_or_default(),
             asset_url: a.browser_download_url.clone(),
             asset_name: a.name.clone(),
+            checksum_url,
+            sig_url,
         }))
     } else {
         bail!("No matching asset for target {needle}");
     }
 }
 
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(bytes).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Parse a `sha256sum`-style checksum file ("<hex>  <filename>" or just "<hex>").
+fn parse_checksum_file(text: &str) -> Option<String> {
+    text.split_whitespace().next().map(|s| s.to_lowercase())
+}
+
+fn hex_decode(s: &str) -> Result<Vec<u8>> {
+    let s = s.trim();
+    anyhow::ensure!(s.len() % 2 == 0, "hex string has odd length");
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| anyhow!("{e}")))
+        .collect()
+}
+
+fn update_verifying_key() -> Result<VerifyingKey> {
+    let bytes = hex_decode(UPDATE_PUBKEY).context("UPDATE_PUBKEY is not valid hex")?;
+    let arr: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow!("UPDATE_PUBKEY must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&arr).context("UPDATE_PUBKEY is not a valid Ed25519 public key")
+}
+
+/// Verify the detached Ed25519 signature over a downloaded release archive.
+/// Returns an error (and refuses installation) on any mismatch.
+pub fn verify_release(archive_bytes: &[u8], sig_bytes: &[u8]) -> Result<()> {
+    let key = update_verifying_key()?;
+    let sig_arr: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| anyhow!("release signature must be 64 bytes"))?;
+    let sig = Signature::from_bytes(&sig_arr);
+    key.verify(archive_bytes, &sig)
+        .map_err(|e| anyhow!("release signature verification failed: {e}"))
+}
+
 pub async fn download_and_extract(info: &UpdateInfo) -> Result<PathBuf> {
     let client = reqwest::Client::new();
     let bytes = client
@@ -141,6 +221,37 @@ pub async fn download_and_extract(info: &UpdateInfo) -> Result<PathBuf> {
         .bytes()
         .await?;
 
+    if let Some(checksum_url) = &info.checksum_url {
+        let checksum_body = client
+            .get(checksum_url)
+            .header(reqwest::header::USER_AGENT, "BestellDesk-updater/1.0")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let expected = parse_checksum_file(&checksum_body)
+            .context("checksum asset did not contain a sha256 hex digest")?;
+        let actual = sha256_hex(&bytes);
+        anyhow::ensure!(
+            actual == expected,
+            "checksum mismatch for {}: expected {expected}, got {actual}",
+            info.asset_name
+        );
+    }
+
+    let sig_bytes = client
+        .get(&info.sig_url)
+        .header(reqwest::header::USER_AGENT, "BestellDesk-updater/1.0")
+        .header(reqwest::header::ACCEPT, "application/octet-stream")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    verify_release(&bytes, &sig_bytes)
+        .with_context(|| format!("refusing to install {}: signature check failed", info.asset_name))?;
+
     let tmp = std::env::temp_dir();
     let archive_path = tmp.join(&info.asset_name);
     fs::write(&archive_path, &bytes)?;
@@ -200,6 +311,11 @@ pub async fn download_and_extract(info: &UpdateInfo) -> Result<PathBuf> {
 pub fn spawn_replacer_and_exit(new_exe: &Path) -> Result<()> {
     let current_exe = std::env::current_exe()?;
 
+    // Keep a copy of the still-working binary so the relaunch script can
+    // restore it if the new one fails to stay up.
+    let backup_exe = current_exe.with_file_name("BestellDesk.bak");
+    fs::copy(&current_exe, &backup_exe).context("backup current binary before update")?;
+
     #[cfg(target_os = "windows")]
     {
         let script = std::env::temp_dir().join("BestellDesk_update.bat");
@@ -209,9 +325,17 @@ pub fn spawn_replacer_and_exit(new_exe: &Path) -> Result<()> {
                 "@echo off\r\n\
                  ping 127.0.0.1 -n 2 > nul\r\n\
                  copy /Y \"{new}\" \"{old}\"\r\n\
-                 start \"\" \"{old}\"\r\n",
+                 start \"\" \"{old}\"\r\n\
+                 ping 127.0.0.1 -n 4 > nul\r\n\
+                 tasklist /FI \"IMAGENAME eq {old_name}\" | find /I \"{old_name}\" > nul\r\n\
+                 if errorlevel 1 (\r\n\
+                     copy /Y \"{bak}\" \"{old}\"\r\n\
+                     start \"\" \"{old}\"\r\n\
+                 )\r\n",
                 new = new_exe.display(),
                 old = current_exe.display(),
+                bak = backup_exe.display(),
+                old_name = current_exe.file_name().and_then(|n| n.to_str()).unwrap_or("BestellDesk.exe"),
             ),
         )?;
         std::process::Command::new("cmd")
@@ -228,9 +352,20 @@ pub fn spawn_replacer_and_exit(new_exe: &Path) -> Result<()> {
                 "#!/bin/sh\n\
                  sleep 1\n\
                  mv \"{new}\" \"{old}\"\n\
-                 exec \"{old}\"\n",
+                 \"{old}\" &\n\
+                 pid=$!\n\
+                 sleep 3\n\
+                 if ! kill -0 $pid 2>/dev/null; then\n\
+                 \x20 wait $pid\n\
+                 \x20 code=$?\n\
+                 \x20 if [ $code -ne 0 ]; then\n\
+                 \x20\x20  cp \"{bak}\" \"{old}\"\n\
+                 \x20\x20  \"{old}\" &\n\
+                 \x20 fi\n\
+                 fi\n",
                 new = new_exe.display(),
                 old = current_exe.display(),
+                bak = backup_exe.display(),
             ),
         )?;
         let _ = std::process::Command::new("sh").arg(&script).spawn()?;
@@ -0,0 +1,84 @@
+// src/services/users.rs
+use anyhow::{anyhow, Result};
+use futures_util::TryStreamExt;
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::IndexOptions;
+use mongodb::{bson::{doc, oid::ObjectId, to_bson}, Collection, IndexModel};
+
+use crate::auth;
+use crate::{db::Db, model::{Role, User}};
+
+/// MongoDB's duplicate-key write-error code.
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+fn coll(db: &Db) -> Collection<User> {
+    db.db.collection("users")
+}
+
+fn is_duplicate_key_error(e: &mongodb::error::Error) -> bool {
+    matches!(
+        e.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(we)) if we.code == DUPLICATE_KEY_CODE
+    )
+}
+
+/// Builds the unique index on `username` that `create_user` relies on to
+/// reject duplicates atomically instead of via a check-then-insert race.
+/// Callable directly, but normally run once via `services::migrations`.
+pub async fn ensure_indexes(db: &Db) -> Result<()> {
+    let unique_username = IndexModel::builder()
+        .keys(doc! { "username": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+    coll(db).create_indexes([unique_username]).await?;
+    Ok(())
+}
+
+pub async fn count(db: &Db) -> Result<i64> {
+    Ok(coll(db).count_documents(doc! {}).await? as i64)
+}
+
+pub async fn list(db: &Db) -> Result<Vec<User>> {
+    let mut cur = coll(db).find(doc! {}).await?;
+    let mut out = Vec::new();
+    while let Some(u) = cur.try_next().await? {
+        out.push(u);
+    }
+    Ok(out)
+}
+
+pub async fn create_user(db: &Db, username: &str, plain: &str, role: Role) -> Result<ObjectId> {
+    let password_hash = auth::hash_password(plain)?;
+    let user = User { id: None, username: username.into(), password_hash, role };
+    let r = coll(db).insert_one(user).await.map_err(|e| {
+        if is_duplicate_key_error(&e) {
+            anyhow!("user exists")
+        } else {
+            e.into()
+        }
+    })?;
+    Ok(r.inserted_id.as_object_id().unwrap())
+}
+
+/// Looks up `username`, verifies `plain` against its stored hash, and
+/// returns the user's role on success.
+pub async fn authenticate(db: &Db, username: &str, plain: &str) -> Result<Option<Role>> {
+    if let Some(u) = coll(db).find_one(doc! { "username": username }).await? {
+        if auth::verify_password(&u.password_hash, plain)? {
+            return Ok(Some(u.role));
+        }
+    }
+    Ok(None)
+}
+
+pub async fn set_role(db: &Db, id: ObjectId, role: Role) -> Result<()> {
+    coll(db)
+        .update_one(doc! { "_id": id }, doc! { "$set": { "role": to_bson(&role)? } })
+        .await?;
+    Ok(())
+}
+
+pub async fn delete(db: &Db, id: ObjectId) -> Result<()> {
+    coll(db).delete_one(doc! { "_id": id }).await?;
+    Ok(())
+}
@@ -1,14 +1,38 @@
 use eframe::egui;
 use mongodb::bson::oid::ObjectId;
 
-use crate::model::{Dish, DishInput, PizzaSize, Supplier, Category};
-use crate::services::{admin_users, dishes, settings, suppliers, categories};
+use crate::model::{Dish, DishInput, Extra, Role, Supplier, Category, VariantGroup, VariantOption};
+use crate::services::dish_repo::DishRepo;
+use crate::services::{settings, suppliers, categories, users};
+use crate::ui::theme::Theme;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum AdminPage { Menu, Suppliers, Dishes, Categories, Settings }
+enum AdminPage { Menu, Suppliers, Dishes, Categories, Orders, Stats, Settings }
+
+/// Scratch state for authoring one `VariantGroup` in `page_dishes`, including
+/// the pending-option input row shown below its existing options.
+#[derive(Clone, Default)]
+struct GroupEditor {
+    name: String,
+    options: Vec<VariantOption>,
+    new_opt_label: String,
+    new_opt_price: i64,
+}
+
+impl GroupEditor {
+    fn into_group(self) -> VariantGroup {
+        VariantGroup { name: self.name, options: self.options }
+    }
+
+    fn from_group(g: &VariantGroup) -> Self {
+        Self { name: g.name.clone(), options: g.options.clone(), new_opt_label: String::new(), new_opt_price: 0 }
+    }
+}
 
 pub struct AdminState {
     page: AdminPage,
+    /// Pages visited before the current one; popped by `back()`.
+    history: Vec<AdminPage>,
 
     supplier_name: String,
     supplier_fee: i64,
@@ -19,20 +43,24 @@ pub struct AdminState {
     dish_name: String,
     dish_price: i64,
     pub sel_supplier_idx: usize,
-    tag_is_pizza: bool,
+    has_variants: bool,
     dish_number: String,
-    pizza_sizes: Vec<PizzaSize>,
-    new_size_label: String,
-    new_size_price: i64,
+    create_groups: Vec<GroupEditor>,
+    create_new_group_name: String,
+    create_extras: Vec<Extra>,
+    create_new_extra_label: String,
+    create_new_extra_price: i64,
 
     edit_id: Option<ObjectId>,
-    edit_is_pizza: bool,
+    edit_has_variants: bool,
     edit_name: String,
     edit_number: String,
     edit_price: i64,
-    edit_sizes: Vec<PizzaSize>,
-    edit_new_size_label: String,
-    edit_new_size_price: i64,
+    edit_groups: Vec<GroupEditor>,
+    edit_new_group_name: String,
+    edit_extras: Vec<Extra>,
+    edit_new_extra_label: String,
+    edit_new_extra_price: i64,
 
     available_categories: Vec<Category>,
     chosen_categories_create: Vec<ObjectId>,
@@ -41,7 +69,7 @@ pub struct AdminState {
     pub cat_new_name: String,
     pub cat_edit_id: Option<ObjectId>,
     pub cat_edit_name: String,
-    pub cat_edit_pos: i64,
+    cat_msg: Option<(bool, String)>,
 
     pub set_supplier_idx: usize,
 
@@ -49,12 +77,77 @@ pub struct AdminState {
     backup_export_path: String,
     backup_import_path: String,
     backup_msg: Option<(bool, String)>,
+
+    csv_export_path: String,
+    csv_collection_idx: usize,
+
+    backup_incremental_dir: String,
+    backup_incremental_index_path: String,
+
+    prune_keep_last: usize,
+    prune_keep_daily: usize,
+    prune_keep_weekly: usize,
+    prune_keep_monthly: usize,
+    prune_keep_yearly: usize,
+
+    catalog: Vec<crate::services::backup::CollectionEntry>,
+    catalog_selection: std::collections::HashSet<String>,
+    catalog_merge: bool,
+
+    keyfile_path: String,
+    keyfile_fingerprint: Option<String>,
+
+    s3_endpoint: String,
+    s3_region: String,
+    s3_bucket: String,
+    s3_access_key: String,
+    s3_secret_key: String,
+    s3_key: String,
+
+    theme: Theme,
+    theme_loaded: bool,
+
+    /// Selected update channel, mirrored here from the on-disk `LocalConfig`
+    /// the first time this page renders (same pattern as `theme`/`theme_loaded`).
+    update_channel: crate::config::UpdateChannel,
+    update_channel_loaded: bool,
+
+    menu_import_dir: String,
+    menu_import_msg: Option<String>,
+
+    menu_export_path: String,
+    menu_export_msg: Option<(bool, String)>,
+
+    streamed_export_path: String,
+    streamed_import_path: String,
+    /// Receiver for the in-flight streamed export/import worker thread, if any.
+    backup_worker_rx: Option<std::sync::mpsc::Receiver<crate::services::backup::Op>>,
+    /// Latest (done, total) reported by the worker thread; drawn as an `egui::ProgressBar`.
+    backup_progress: Option<(usize, usize)>,
+
+    stats_days_back: i64,
+    stats_supplier_idx: usize,
+    stats_bucket: crate::services::stats::Bucket,
+
+    orders_supplier_idx: usize,
+    orders_days_back: i64,
+    receipt_export_dir: String,
+    receipt_msg: Option<(bool, String)>,
+
+    /// Resolved role of the currently logged-in user, mirrored here each
+    /// frame from `render`'s `role` out-parameter so pages can gate on it.
+    role: Role,
+    new_user_name: String,
+    new_user_pass: String,
+    new_user_role_idx: usize,
+    users_msg: Option<(bool, String)>,
 }
 
 impl Default for AdminState {
     fn default() -> Self {
         Self {
             page: AdminPage::Menu,
+            history: vec![],
 
             supplier_name: String::new(),
             supplier_fee: 0,
@@ -65,20 +158,24 @@ impl Default for AdminState {
             dish_name: String::new(),
             dish_price: 0,
             sel_supplier_idx: 0,
-            tag_is_pizza: false,
+            has_variants: false,
             dish_number: String::new(),
-            pizza_sizes: vec![],
-            new_size_label: String::new(),
-            new_size_price: 0,
+            create_groups: vec![],
+            create_new_group_name: String::new(),
+            create_extras: vec![],
+            create_new_extra_label: String::new(),
+            create_new_extra_price: 0,
 
             edit_id: None,
-            edit_is_pizza: false,
+            edit_has_variants: false,
             edit_name: String::new(),
             edit_number: String::new(),
             edit_price: 0,
-            edit_sizes: vec![],
-            edit_new_size_label: String::new(),
-            edit_new_size_price: 0,
+            edit_groups: vec![],
+            edit_new_group_name: String::new(),
+            edit_extras: vec![],
+            edit_new_extra_label: String::new(),
+            edit_new_extra_price: 0,
 
             available_categories: vec![],
             chosen_categories_create: vec![],
@@ -87,7 +184,7 @@ impl Default for AdminState {
             cat_new_name: String::new(),
             cat_edit_id: None,
             cat_edit_name: String::new(),
-            cat_edit_pos: 0,
+            cat_msg: None,
 
             set_supplier_idx: 0,
 
@@ -95,6 +192,81 @@ impl Default for AdminState {
             backup_export_path: "backup.json.enc".to_string(),
             backup_import_path: String::new(),
             backup_msg: None,
+
+            csv_export_path: "orders.csv".to_string(),
+            csv_collection_idx: 0,
+
+            backup_incremental_dir: "backups".to_string(),
+            backup_incremental_index_path: String::new(),
+
+            prune_keep_last: 3,
+            prune_keep_daily: 7,
+            prune_keep_weekly: 4,
+            prune_keep_monthly: 12,
+            prune_keep_yearly: 5,
+
+            catalog: vec![],
+            catalog_selection: std::collections::HashSet::new(),
+            catalog_merge: false,
+
+            keyfile_path: "backup.key".to_string(),
+            keyfile_fingerprint: None,
+
+            s3_endpoint: String::new(),
+            s3_region: String::new(),
+            s3_bucket: String::new(),
+            s3_access_key: String::new(),
+            s3_secret_key: String::new(),
+            s3_key: "backups/BestellDesk/latest.bdk".to_string(),
+
+            theme: Theme::default(),
+            theme_loaded: false,
+
+            update_channel: crate::config::UpdateChannel::default(),
+            update_channel_loaded: false,
+
+            menu_import_dir: "menu_import".to_string(),
+            menu_import_msg: None,
+
+            menu_export_path: "menu.html".to_string(),
+            menu_export_msg: None,
+
+            streamed_export_path: "backup.bdks".to_string(),
+            streamed_import_path: String::new(),
+            backup_worker_rx: None,
+            backup_progress: None,
+
+            stats_days_back: 30,
+            stats_supplier_idx: 0,
+            stats_bucket: crate::services::stats::Bucket::default(),
+
+            orders_supplier_idx: 0,
+            orders_days_back: 7,
+            receipt_export_dir: "receipts".to_string(),
+            receipt_msg: None,
+
+            role: Role::Viewer,
+            new_user_name: String::new(),
+            new_user_pass: String::new(),
+            new_user_role_idx: 0,
+            users_msg: None,
+        }
+    }
+}
+
+impl AdminState {
+    /// Navigates to `page`, remembering the current one so `back()` can return to it.
+    fn push_page(&mut self, page: AdminPage) {
+        if page != self.page {
+            self.history.push(self.page);
+            self.page = page;
+        }
+    }
+
+    /// Returns to the previous page, if any.
+    fn back(&mut self) {
+        if let Some(prev) = self.history.pop() {
+            self.page = prev;
         }
     }
 }
@@ -109,12 +281,31 @@ pub fn render(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     user: &mut String,
     pass: &mut String,
     authed: &mut bool,
+    role: &mut Option<Role>,
     state: &mut AdminState,
 ) {
-    let need_bootstrap = rt.block_on(admin_users::count(db)).unwrap_or(0) == 0;
+    if !state.theme_loaded {
+        if let Ok(Some(name)) = rt.block_on(settings::get_theme_name(db)) {
+            if let Some(t) = Theme::from_name(&name) {
+                state.theme = t;
+            }
+        }
+        state.theme_loaded = true;
+    }
+    ui.ctx().set_visuals(state.theme.visuals());
+
+    if !state.update_channel_loaded {
+        if let Ok(cfg) = crate::config::load() {
+            state.update_channel = cfg.update_channel;
+        }
+        state.update_channel_loaded = true;
+    }
+
+    let need_bootstrap = rt.block_on(users::count(db)).unwrap_or(0) == 0;
     if need_bootstrap {
         ui.heading("Create first admin user");
         ui.label("Username");
@@ -122,9 +313,9 @@ pub fn render(
         ui.label("Password");
         ui.add(egui::TextEdit::singleline(pass).password(true));
         if ui.button("Create admin").clicked() {
-            match rt.block_on(admin_users::create(db, user, pass)) {
-                Ok(_) => { *authed = true; pass.clear(); }
-                Err(e) => { ui.colored_label(egui::Color32::RED, e.to_string()); }
+            match rt.block_on(users::create_user(db, user, pass, Role::Owner)) {
+                Ok(_) => { *authed = true; *role = Some(Role::Owner); pass.clear(); }
+                Err(e) => { ui.colored_label(state.theme.error_color(), e.to_string()); }
             };
         }
         return;
@@ -137,28 +328,43 @@ pub fn render(
         ui.label("Password");
         ui.add(egui::TextEdit::singleline(pass).password(true));
         if ui.button("Login").clicked() {
-            let ok = rt.block_on(admin_users::verify(db, user, pass)).unwrap_or(false);
-            if ok { *authed = true; pass.clear(); }
-            else { ui.colored_label(egui::Color32::RED, "Invalid credentials"); }
+            match rt.block_on(users::authenticate(db, user, pass)) {
+                Ok(Some(r)) => { *authed = true; *role = Some(r); pass.clear(); }
+                Ok(None) => { ui.colored_label(state.theme.error_color(), "Invalid credentials"); }
+                Err(e) => { ui.colored_label(state.theme.error_color(), e.to_string()); }
+            }
         }
         return;
     }
 
-    ui.horizontal(|ui| {
-        if ui.button("Menu").clicked()       { state.page = AdminPage::Menu; }
-        if ui.button("Suppliers").clicked()  { state.page = AdminPage::Suppliers; }
-        if ui.button("Dishes").clicked()     { state.page = AdminPage::Dishes; }
-        if ui.button("Categories").clicked() { state.page = AdminPage::Categories; }
-        if ui.button("Settings").clicked()   { state.page = AdminPage::Settings; }
+    state.role = role.unwrap_or(Role::Viewer);
+
+    egui::SidePanel::left("admin_nav_panel").show_inside(ui, |ui| {
+        let can_go_back = !state.history.is_empty();
+        let back_resp = ui.add_enabled(can_go_back, egui::Button::new("← Back"));
+        let back_resp = if can_go_back { back_resp } else { back_resp.on_hover_cursor(egui::CursorIcon::NotAllowed) };
+        if back_resp.clicked() {
+            state.back();
+        }
+        ui.separator();
+
+        if ui.button("Menu").clicked()       { state.push_page(AdminPage::Menu); }
+        if ui.button("Suppliers").clicked()  { state.push_page(AdminPage::Suppliers); }
+        if ui.button("Dishes").clicked()     { state.push_page(AdminPage::Dishes); }
+        if ui.button("Categories").clicked() { state.push_page(AdminPage::Categories); }
+        if ui.button("Orders").clicked()     { state.push_page(AdminPage::Orders); }
+        if ui.button("Stats").clicked()      { state.push_page(AdminPage::Stats); }
+        if ui.button("Settings").clicked()   { state.push_page(AdminPage::Settings); }
     });
-    ui.separator();
 
     match state.page {
         AdminPage::Menu => { ui.heading("Admin"); ui.label("Choose a section above."); }
-        AdminPage::Suppliers => page_suppliers(ui, rt, db, state),
-        AdminPage::Dishes => page_dishes(ui, rt, db, state),
-        AdminPage::Categories => page_categories(ui, rt, db, state),
-        AdminPage::Settings => page_settings(ui, rt, db, state),
+        AdminPage::Suppliers => page_suppliers(ui, rt, db, repo, state),
+        AdminPage::Dishes => page_dishes(ui, rt, db, repo, state),
+        AdminPage::Categories => page_categories(ui, rt, db, repo, state),
+        AdminPage::Orders => page_orders(ui, rt, db, repo, state),
+        AdminPage::Stats => page_stats(ui, rt, db, repo, state),
+        AdminPage::Settings => page_settings(ui, rt, db, repo, state),
     }
 }
 
@@ -168,9 +374,14 @@ fn page_suppliers(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     state: &mut AdminState,
 ) {
     ui.heading("Suppliers");
+    let can_edit = state.role.can_edit();
+    if !can_edit {
+        ui.colored_label(state.theme.error_color(), "Viewer role: read-only");
+    }
 
     ui.separator();
     ui.label("Create supplier");
@@ -181,7 +392,7 @@ fn page_suppliers(
                 .range(0..=10_000)
                 .prefix("Delivery fee (cents): "),
         );
-        if ui.button("Create").clicked() && !state.supplier_name.trim().is_empty() {
+        if ui.add_enabled(can_edit, egui::Button::new("Create")).clicked() && !state.supplier_name.trim().is_empty() {
             let _ = rt.block_on(suppliers::create(
                 db,
                 &state.supplier_name,
@@ -193,17 +404,17 @@ fn page_suppliers(
 
     ui.separator();
     ui.label("Existing suppliers");
-    let list = rt.block_on(suppliers::list(db)).unwrap_or_default();
+    let list = rt.block_on(repo.list_suppliers()).unwrap_or_default();
     for s in list {
         ui.horizontal(|ui| {
             ui.label(format!("{} (fee: {} cents)", s.name, s.delivery_fee_cents));
             if let Some(id) = s.id {
-                if ui.button("Edit").clicked() {
+                if ui.add_enabled(can_edit, egui::Button::new("Edit")).clicked() {
                     state.edit_supplier_id = Some(id);
                     state.edit_supplier_name = s.name.clone();
                     state.edit_supplier_fee = s.delivery_fee_cents;
                 }
-                if ui.button("Delete").clicked() {
+                if ui.add_enabled(can_edit, egui::Button::new("Delete")).clicked() {
                     let _ = rt.block_on(suppliers::delete(db, id));
                 }
             }
@@ -224,7 +435,7 @@ fn page_suppliers(
         });
 
         ui.horizontal(|ui| {
-            if ui.button("Save").clicked() {
+            if ui.add_enabled(can_edit, egui::Button::new("Save")).clicked() {
                 let _ = rt.block_on(suppliers::update(
                     db,
                     eid,
@@ -257,28 +468,120 @@ fn parse_nr_key(nr_opt: &Option<String>) -> i64 {
 }
 
 fn row_label(d: &Dish) -> String {
-    if d.tags.iter().any(|t| t == "Pizza") {
-        let nr = d.number.clone().unwrap_or_default();
-        let sizes = d.pizza_sizes.as_ref().map(|v| {
-            v.iter().map(|p| format!("{} {}", p.label, eur(p.price_cents))).collect::<Vec<_>>().join(", ")
-        }).unwrap_or_default();
-        if nr.is_empty() { format!("Pizza: {} [{}]", d.name, sizes) }
-        else { format!("Pizza Nr. {}: {} [{}]", nr, d.name, sizes) }
+    let nr = d.number.clone().unwrap_or_default();
+    let base = if nr.is_empty() { d.name.clone() } else { format!("{}: {}", nr, d.name) };
+
+    if let Some(groups) = d.variant_groups.as_ref().filter(|g| !g.is_empty()) {
+        let parts = groups.iter().map(|g| {
+            let opts = g.options.iter().map(|o| format!("{} {}", o.label, eur(o.price_cents))).collect::<Vec<_>>().join(", ");
+            format!("{}: [{}]", g.name, opts)
+        }).collect::<Vec<_>>().join("; ");
+        format!("{base} — {parts}")
+    } else if let Some(sizes) = d.pizza_sizes.as_ref().filter(|s| !s.is_empty()) {
+        // Legacy pizza-only dishes created before the variant-group system.
+        let sizes = sizes.iter().map(|p| format!("{} {}", p.label, eur(p.price_cents))).collect::<Vec<_>>().join(", ");
+        format!("{base} [{sizes}]")
     } else {
-        let nr = d.number.clone().unwrap_or_default();
-        if nr.is_empty() { format!("{} ({})", d.name, eur(d.price_cents)) }
-        else { format!("{}: {} ({})", nr, d.name, eur(d.price_cents)) }
+        format!("{base} ({})", eur(d.price_cents))
+    }
+}
+
+/// Renders one option/price editor block per variant group (plus a button to
+/// add a new group), and an extras editor below. Generalizes the old
+/// pizza-only sizes editor to any number of named groups.
+fn variant_editor(
+    ui: &mut egui::Ui,
+    groups: &mut Vec<GroupEditor>,
+    new_group_name: &mut String,
+    extras: &mut Vec<Extra>,
+    new_extra_label: &mut String,
+    new_extra_price: &mut i64,
+) {
+    ui.separator();
+    ui.label("Variant groups");
+
+    let mut remove_group: Option<usize> = None;
+    for (gi, g) in groups.iter_mut().enumerate() {
+        ui.push_id(("group", gi), |ui| {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Group name");
+                    ui.text_edit_singleline(&mut g.name);
+                    if ui.button("Remove group").clicked() { remove_group = Some(gi); }
+                });
+
+                let mut remove_opt: Option<usize> = None;
+                for (oi, opt) in g.options.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("#{oi}"));
+                        ui.text_edit_singleline(&mut opt.label);
+                        ui.label("Price (cents)");
+                        ui.add(egui::DragValue::new(&mut opt.price_cents).range(0..=100_000));
+                        if ui.button("Remove").clicked() { remove_opt = Some(oi); }
+                    });
+                }
+                if let Some(i) = remove_opt { if i < g.options.len() { g.options.remove(i); } }
+
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut g.new_opt_label);
+                    ui.add(egui::DragValue::new(&mut g.new_opt_price).range(0..=100_000).prefix("Price (cents): "));
+                    if ui.button("Add option").clicked() && !g.new_opt_label.trim().is_empty() {
+                        g.options.push(VariantOption { label: g.new_opt_label.clone(), price_cents: g.new_opt_price });
+                        g.new_opt_label.clear();
+                        g.new_opt_price = 0;
+                    }
+                });
+            });
+        });
     }
+    if let Some(i) = remove_group { if i < groups.len() { groups.remove(i); } }
+
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(new_group_name);
+        if ui.button("Add group").clicked() && !new_group_name.trim().is_empty() {
+            groups.push(GroupEditor { name: new_group_name.clone(), ..Default::default() });
+            new_group_name.clear();
+        }
+    });
+
+    ui.separator();
+    ui.label("Extras");
+    let mut remove_extra: Option<usize> = None;
+    for (ei, ex) in extras.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("#{ei}"));
+            ui.text_edit_singleline(&mut ex.label);
+            ui.label("Surcharge (cents)");
+            ui.add(egui::DragValue::new(&mut ex.price_cents).range(0..=100_000));
+            if ui.button("Remove").clicked() { remove_extra = Some(ei); }
+        });
+    }
+    if let Some(i) = remove_extra { if i < extras.len() { extras.remove(i); } }
+
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(new_extra_label);
+        ui.add(egui::DragValue::new(new_extra_price).range(0..=100_000).prefix("Surcharge (cents): "));
+        if ui.button("Add extra").clicked() && !new_extra_label.trim().is_empty() {
+            extras.push(Extra { label: new_extra_label.clone(), price_cents: *new_extra_price });
+            new_extra_label.clear();
+            *new_extra_price = 0;
+        }
+    });
 }
 
 fn page_dishes(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     state: &mut AdminState,
 ) {
     ui.heading("Dishes");
-    let sups = rt.block_on(suppliers::list(db)).unwrap_or_default();
+    let can_edit = state.role.can_edit();
+    if !can_edit {
+        ui.colored_label(state.theme.error_color(), "Viewer role: read-only");
+    }
+    let sups = rt.block_on(repo.list_suppliers()).unwrap_or_default();
     if sups.is_empty() { ui.label("No suppliers yet."); return; }
     if state.sel_supplier_idx >= sups.len() { state.sel_supplier_idx = 0; }
     let sid = sups[state.sel_supplier_idx].id.unwrap();
@@ -314,69 +617,54 @@ fn page_dishes(
         ui.label("Nr.");
         ui.text_edit_singleline(&mut state.dish_number);
         ui.text_edit_singleline(&mut state.dish_name);
-        ui.toggle_value(&mut state.tag_is_pizza, "Pizza");
+        ui.toggle_value(&mut state.has_variants, "Has variants");
 
-        if state.tag_is_pizza {
+        if state.has_variants {
             ui.add_enabled(false, egui::DragValue::new(&mut state.dish_price).prefix("€ disabled"));
         } else {
             ui.add(egui::DragValue::new(&mut state.dish_price).range(0..=100_000).prefix("Price (cents): "));
         }
     });
 
-    if state.tag_is_pizza {
-        ui.separator();
-        ui.label("Pizza sizes");
-        let mut remove_idx: Option<usize> = None;
-        for idx in 0..state.pizza_sizes.len() {
-            ui.horizontal(|ui| {
-                ui.label(format!("#{idx}"));
-                let l_ref: *mut String = &mut state.pizza_sizes[idx].label;
-                let p_ref: *mut i64 = &mut state.pizza_sizes[idx].price_cents;
-                unsafe {
-                    ui.text_edit_singleline(&mut *l_ref);
-                    ui.label("Price (cents)");
-                    ui.add(egui::DragValue::new(&mut *p_ref).range(0..=100_000));
-                }
-                if ui.button("Remove").clicked() { remove_idx = Some(idx); }
-            });
-        }
-        if let Some(i) = remove_idx { if i < state.pizza_sizes.len() { state.pizza_sizes.remove(i); } }
-
-        ui.horizontal(|ui| {
-            ui.text_edit_singleline(&mut state.new_size_label);
-            ui.add(egui::DragValue::new(&mut state.new_size_price).range(0..=100_000).prefix("Price (cents): "));
-            if ui.button("Add size").clicked() && !state.new_size_label.trim().is_empty() {
-                state.pizza_sizes.push(PizzaSize { label: state.new_size_label.clone(), price_cents: state.new_size_price });
-                state.new_size_label.clear();
-                state.new_size_price = 0;
-            }
-        });
+    if state.has_variants {
+        variant_editor(
+            ui,
+            &mut state.create_groups,
+            &mut state.create_new_group_name,
+            &mut state.create_extras,
+            &mut state.create_new_extra_label,
+            &mut state.create_new_extra_price,
+        );
     }
 
-    if ui.button("Create").clicked() {
-        if state.tag_is_pizza {
+    if ui.add_enabled(can_edit, egui::Button::new("Create")).clicked() {
+        if state.has_variants {
+            let groups: Vec<VariantGroup> = state.create_groups.iter().cloned().map(GroupEditor::into_group).collect();
             let input = DishInput {
                 supplier_id: sid,
                 name: state.dish_name.trim().to_string(),
                 price_cents: None,
-                tags: vec!["Pizza".to_string()],
+                tags: vec![],
                 number: if state.dish_number.trim().is_empty() { None } else { Some(state.dish_number.trim().to_string()) },
-                pizza_sizes: if state.pizza_sizes.is_empty() { None } else { Some(state.pizza_sizes.clone()) },
+                pizza_sizes: None,
+                variant_groups: if groups.is_empty() { None } else { Some(groups) },
+                extras: if state.create_extras.is_empty() { None } else { Some(state.create_extras.clone()) },
                 categories: Some(state.chosen_categories_create.clone()),
             };
-            if !input.name.is_empty() && input.pizza_sizes.is_some() {
-                let _ = rt.block_on(dishes::create_with_tags(db, input));
+            if !input.name.is_empty() && input.variant_groups.is_some() {
+                let _ = rt.block_on(repo.create_with_variants(input));
                 state.dish_name.clear();
                 state.dish_number.clear();
-                state.pizza_sizes.clear();
-                state.new_size_label.clear();
-                state.new_size_price = 0;
-                state.tag_is_pizza = false;
+                state.create_groups.clear();
+                state.create_new_group_name.clear();
+                state.create_extras.clear();
+                state.create_new_extra_label.clear();
+                state.create_new_extra_price = 0;
+                state.has_variants = false;
                 state.chosen_categories_create.clear();
             }
         } else if !state.dish_name.trim().is_empty() {
-            let _ = rt.block_on(dishes::create_plain(
-                db,
+            let _ = rt.block_on(repo.create_plain(
                 sid,
                 &state.dish_name,
                 if state.dish_number.trim().is_empty() { None } else { Some(state.dish_number.trim().to_string()) },
@@ -393,30 +681,44 @@ fn page_dishes(
     ui.separator();
     ui.label("Existing dishes");
 
-    let mut dlist = rt.block_on(dishes::list_by_supplier(db, sid)).unwrap_or_default();
+    let mut dlist = rt.block_on(repo.list_by_supplier(sid)).unwrap_or_default();
     dlist.sort_by_key(|d| parse_nr_key(&d.number));
 
     for d in dlist {
         ui.horizontal(|ui| {
             ui.label(row_label(&d));
             if let Some(id) = d.id {
-                if ui.button("Edit").clicked() {
+                if ui.add_enabled(can_edit, egui::Button::new("Edit")).clicked() {
                     state.edit_id = Some(id);
-                    let is_pizza = d.tags.iter().any(|t| t == "Pizza");
-                    state.edit_is_pizza = is_pizza;
                     state.edit_name = d.name.clone();
                     state.edit_number = d.number.clone().unwrap_or_default();
                     state.chosen_categories_edit = d.categories.clone();
-                    if is_pizza {
-                        state.edit_sizes = d.pizza_sizes.clone().unwrap_or_default();
+
+                    // Migrate legacy pizza_sizes into a single "Size" group for editing.
+                    let groups: Vec<GroupEditor> = d.variant_groups.clone()
+                        .filter(|g| !g.is_empty())
+                        .or_else(|| d.pizza_sizes.clone().filter(|s| !s.is_empty()).map(|sizes| {
+                            vec![VariantGroup {
+                                name: "Size".to_string(),
+                                options: sizes.into_iter().map(|s| VariantOption { label: s.label, price_cents: s.price_cents }).collect(),
+                            }]
+                        }))
+                        .unwrap_or_default()
+                        .iter()
+                        .map(GroupEditor::from_group)
+                        .collect();
+                    state.edit_has_variants = !groups.is_empty();
+                    state.edit_groups = groups;
+                    state.edit_extras = d.extras.clone().unwrap_or_default();
+
+                    if state.edit_has_variants {
                         state.edit_price = 0;
                     } else {
                         state.edit_price = d.price_cents;
-                        state.edit_sizes.clear();
                     }
                 }
-                if ui.button("Delete").clicked() {
-                    let _ = rt.block_on(dishes::delete(db, id));
+                if ui.add_enabled(can_edit, egui::Button::new("Delete")).clicked() {
+                    let _ = rt.block_on(repo.delete(id));
                 }
             }
         });
@@ -444,57 +746,40 @@ fn page_dishes(
             ui.label("Nr.");
             ui.text_edit_singleline(&mut state.edit_number);
             ui.text_edit_singleline(&mut state.edit_name);
-            if state.edit_is_pizza {
+            ui.toggle_value(&mut state.edit_has_variants, "Has variants");
+            if state.edit_has_variants {
                 ui.add_enabled(false, egui::DragValue::new(&mut state.edit_price).prefix("€ disabled"));
             } else {
                 ui.add(egui::DragValue::new(&mut state.edit_price).range(0..=100_000).prefix("Price (cents): "));
             }
         });
 
-        if state.edit_is_pizza {
-            ui.label("Pizza sizes");
-            let mut remove_idx: Option<usize> = None;
-            for idx in 0..state.edit_sizes.len() {
-                ui.horizontal(|ui| {
-                    ui.label(format!("#{idx}"));
-                    let l_ref: *mut String = &mut state.edit_sizes[idx].label;
-                    let p_ref: *mut i64 = &mut state.edit_sizes[idx].price_cents;
-                    unsafe {
-                        ui.label("Label");
-                        ui.text_edit_singleline(&mut *l_ref);
-                        ui.label("Price (cents)");
-                        ui.add(egui::DragValue::new(&mut *p_ref).range(0..=100_000));
-                    }
-                    if ui.button("Remove").clicked() { remove_idx = Some(idx); }
-                });
-            }
-            if let Some(i) = remove_idx { if i < state.edit_sizes.len() { state.edit_sizes.remove(i); } }
-
-            ui.horizontal(|ui| {
-                ui.text_edit_singleline(&mut state.edit_new_size_label);
-                ui.add(egui::DragValue::new(&mut state.edit_new_size_price).range(0..=100_000).prefix("Price (cents): "));
-                if ui.button("Add size").clicked() && !state.edit_new_size_label.trim().is_empty() {
-                    state.edit_sizes.push(PizzaSize { label: state.edit_new_size_label.clone(), price_cents: state.edit_new_size_price });
-                    state.edit_new_size_label.clear();
-                    state.edit_new_size_price = 0;
-                }
-            });
+        if state.edit_has_variants {
+            variant_editor(
+                ui,
+                &mut state.edit_groups,
+                &mut state.edit_new_group_name,
+                &mut state.edit_extras,
+                &mut state.edit_new_extra_label,
+                &mut state.edit_new_extra_price,
+            );
         }
 
         ui.horizontal(|ui| {
-            if ui.button("Save").clicked() {
-                if state.edit_is_pizza {
-                    let _ = rt.block_on(dishes::update_pizza(
-                        db,
+            if ui.add_enabled(can_edit, egui::Button::new("Save")).clicked() {
+                if state.edit_has_variants {
+                    let groups: Vec<VariantGroup> = state.edit_groups.iter().cloned().map(GroupEditor::into_group).collect();
+                    let _ = rt.block_on(repo.update_with_variants(
                         eid,
                         &state.edit_name,
                         if state.edit_number.trim().is_empty() { None } else { Some(state.edit_number.trim().to_string()) },
-                        state.edit_sizes.clone(),
+                        state.edit_price,
+                        groups,
+                        state.edit_extras.clone(),
                         state.chosen_categories_edit.clone(),
                     ));
                 } else {
-                    let _ = rt.block_on(dishes::update_plain(
-                        db,
+                    let _ = rt.block_on(repo.update_plain(
                         eid,
                         &state.edit_name,
                         if state.edit_number.trim().is_empty() { None } else { Some(state.edit_number.trim().to_string()) },
@@ -505,7 +790,8 @@ fn page_dishes(
                 state.edit_id = None;
                 state.edit_name.clear();
                 state.edit_number.clear();
-                state.edit_sizes.clear();
+                state.edit_groups.clear();
+                state.edit_extras.clear();
                 state.edit_price = 0;
                 state.chosen_categories_edit.clear();
             }
@@ -513,12 +799,36 @@ fn page_dishes(
                 state.edit_id = None;
                 state.edit_name.clear();
                 state.edit_number.clear();
-                state.edit_sizes.clear();
+                state.edit_groups.clear();
+                state.edit_extras.clear();
                 state.edit_price = 0;
                 state.chosen_categories_edit.clear();
             }
         });
     }
+
+    ui.separator();
+    ui.heading("Bulk menu import (.json/.csv)");
+    ui.horizontal(|ui| {
+        ui.label("Ordner");
+        ui.text_edit_singleline(&mut state.menu_import_dir);
+        if ui.add_enabled(can_edit, egui::Button::new("Importieren")).clicked() {
+            match rt.block_on(crate::services::menu_import::import_folder(db, sid, state.menu_import_dir.trim())) {
+                Ok(r) => {
+                    let mut msg = format!("{} importiert, {} Duplikate übersprungen", r.inserted, r.skipped_duplicate);
+                    if !r.errors.is_empty() {
+                        let details: Vec<String> = r.errors.iter().map(|(f, e)| format!("{f}: {e}")).collect();
+                        msg.push_str(&format!(", {} Fehler: {}", r.errors.len(), details.join("; ")));
+                    }
+                    state.menu_import_msg = Some(msg);
+                }
+                Err(e) => state.menu_import_msg = Some(format!("Import fehlgeschlagen: {e}")),
+            }
+        }
+    });
+    if let Some(msg) = &state.menu_import_msg {
+        ui.label(msg);
+    }
 }
 
 /* ---------------- Categories page ---------------- */
@@ -527,14 +837,19 @@ fn page_categories(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     state: &mut AdminState,
 ) {
-    use crate::services::{categories, suppliers};
+    use crate::services::categories;
 
     ui.heading("Categories");
+    let can_edit = state.role.can_edit();
+    if !can_edit {
+        ui.colored_label(state.theme.error_color(), "Viewer role: read-only");
+    }
 
     // 1) Supplier wählen
-    let sups = rt.block_on(suppliers::list(db)).unwrap_or_default();
+    let sups = rt.block_on(repo.list_suppliers()).unwrap_or_default();
     if sups.is_empty() {
         ui.label("No suppliers yet.");
         return;
@@ -558,14 +873,23 @@ fn page_categories(
     // 2) Neue Category anlegen
     ui.horizontal(|ui| {
         ui.text_edit_singleline(&mut state.cat_new_name);
-        if ui.button("Add category").clicked() {
+        if ui.add_enabled(can_edit, egui::Button::new("Add category")).clicked() {
             let name = state.cat_new_name.trim();
             if !name.is_empty() {
-                let _ = rt.block_on(categories::create(db, sid, name));
-                state.cat_new_name.clear();
+                match rt.block_on(categories::create(db, sid, name)) {
+                    Ok(_) => {
+                        state.cat_new_name.clear();
+                        state.cat_msg = None;
+                    }
+                    Err(e) => state.cat_msg = Some((false, e.to_string())),
+                }
             }
         }
     });
+    if let Some((ok, msg)) = &state.cat_msg {
+        let color = if *ok { egui::Color32::from_rgb(20, 160, 20) } else { state.theme.error_color() };
+        ui.colored_label(color, msg);
+    }
 
     ui.separator();
     ui.label("Existing categories");
@@ -573,56 +897,298 @@ fn page_categories(
     // 3) Liste anzeigen
     let cats = rt.block_on(categories::list_by_supplier(db, sid)).unwrap_or_default();
 
-    for c in &cats {
+    for (i, c) in cats.iter().enumerate() {
         ui.horizontal(|ui| {
-            ui.monospace(format!("#{} {}", c.position, c.name));
+            ui.monospace(&c.name);
 
-            if ui.button("Edit").clicked() {
+            if ui.add_enabled(can_edit && i > 0, egui::Button::new("↑")).clicked() {
+                if let Some(id) = c.id {
+                    let _ = rt.block_on(categories::move_up(db, sid, id));
+                }
+            }
+            if ui.add_enabled(can_edit && i + 1 < cats.len(), egui::Button::new("↓")).clicked() {
+                if let Some(id) = c.id {
+                    let _ = rt.block_on(categories::move_down(db, sid, id));
+                }
+            }
+
+            if ui.add_enabled(can_edit, egui::Button::new("Edit")).clicked() {
                 state.cat_edit_id = c.id;
                 state.cat_edit_name = c.name.clone();
-                state.cat_edit_pos = c.position;
             }
 
-            if ui.button("Delete").clicked() {
+            if ui.add_enabled(can_edit, egui::Button::new("Delete")).clicked() {
                 if let Some(id) = c.id {
-                    let _ = rt.block_on(categories::delete(db, id));
+                    match rt.block_on(categories::delete(db, id)) {
+                        Ok(_) => state.cat_msg = None,
+                        Err(e) => state.cat_msg = Some((false, e.to_string())),
+                    }
                 }
             }
         });
     }
 
-    // 4) Edit-Form (Position + Name)
+    // 4) Edit-Form (nur Name; Reihenfolge wird über die ↑/↓-Buttons gesteuert)
     if let Some(edit_id) = state.cat_edit_id {
         ui.separator();
         ui.heading("Edit category");
 
         ui.horizontal(|ui| {
-            ui.label("Position");
-            ui.add(egui::DragValue::new(&mut state.cat_edit_pos).range(0..=10_000));
             ui.label("Name");
             ui.text_edit_singleline(&mut state.cat_edit_name);
         });
 
         ui.horizontal(|ui| {
-            if ui.button("Save").clicked() {
+            if ui.add_enabled(can_edit, egui::Button::new("Save")).clicked() {
                 let name = state.cat_edit_name.trim().to_string();
                 if !name.is_empty() {
-                    let _ = rt.block_on(categories::update(db, edit_id, &name, state.cat_edit_pos));
-                    // UI-State zurücksetzen
-                    state.cat_edit_id = None;
-                    state.cat_edit_name.clear();
-                    state.cat_edit_pos = 0;
+                    match rt.block_on(categories::rename(db, edit_id, &name)) {
+                        Ok(_) => {
+                            // UI-State zurücksetzen
+                            state.cat_edit_id = None;
+                            state.cat_edit_name.clear();
+                            state.cat_msg = None;
+                        }
+                        Err(e) => state.cat_msg = Some((false, e.to_string())),
+                    }
                 }
             }
             if ui.button("Cancel").clicked() {
                 state.cat_edit_id = None;
                 state.cat_edit_name.clear();
-                state.cat_edit_pos = 0;
             }
         });
+    }
+}
+
+/* ---------------- Orders ---------------- */
+
+fn page_orders(
+    ui: &mut egui::Ui,
+    rt: &tokio::runtime::Runtime,
+    db: &crate::db::Db,
+    repo: &dyn DishRepo,
+    state: &mut AdminState,
+) {
+    use crate::services::invoices;
+
+    ui.heading("Orders");
+
+    let sups = rt.block_on(repo.list_suppliers()).unwrap_or_default();
+    if sups.is_empty() {
+        ui.label("No suppliers yet.");
+        return;
+    }
+    if state.orders_supplier_idx >= sups.len() {
+        state.orders_supplier_idx = 0;
+    }
+
+    egui::ComboBox::from_label("Supplier")
+        .selected_text(sups[state.orders_supplier_idx].name.clone())
+        .show_ui(ui, |cb| {
+            for (i, s) in sups.iter().enumerate() {
+                cb.selectable_value(&mut state.orders_supplier_idx, i, s.name.clone());
+            }
+        });
+    let sid = sups[state.orders_supplier_idx].id.unwrap();
+
+    ui.horizontal(|ui| {
+        ui.label("Zeitraum (Tage zurück)");
+        ui.add(egui::DragValue::new(&mut state.orders_days_back).range(1..=3650));
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Rechnungs-Ordner");
+        ui.text_edit_singleline(&mut state.receipt_export_dir);
+    });
+    if let Some((ok, msg)) = &state.receipt_msg {
+        let color = if *ok { egui::Color32::from_rgb(20, 160, 20) } else { state.theme.error_color() };
+        ui.colored_label(color, msg);
+    }
+
+    let to = mongodb::bson::DateTime::now();
+    let from = mongodb::bson::DateTime::from_millis(to.timestamp_millis() - state.orders_days_back * 86_400_000);
+
+    let run_orders: Vec<crate::model::Order> = rt
+        .block_on(crate::services::orders::list_by_supplier(db, sid))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|o| o.created_at >= from && o.created_at <= to)
+        .collect();
 
-        ui.label("Hinweis: Positionen werden aufsteigend sortiert. Bei gleichen Positionen entscheidet der Name.");
+    ui.separator();
+
+    if run_orders.is_empty() {
+        ui.label("Keine Bestellungen in diesem Zeitraum.");
+        return;
     }
+
+    if ui.button("Rechnung für gesamten Lieferzeitraum erzeugen").clicked() {
+        match rt.block_on(invoices::build_for_supplier_run(db, sid, from, to)) {
+            Ok(inv) => {
+                let dir = state.receipt_export_dir.trim();
+                let _ = std::fs::create_dir_all(dir);
+                let path = format!("{dir}/{}.html", inv.invoice_number);
+                match std::fs::write(&path, invoices::render_html(&inv)) {
+                    Ok(_) => state.receipt_msg = Some((true, format!("Rechnung gespeichert: {path}"))),
+                    Err(e) => state.receipt_msg = Some((false, format!("Schreiben fehlgeschlagen: {e}"))),
+                }
+            }
+            Err(e) => state.receipt_msg = Some((false, format!("Rechnung fehlgeschlagen: {e}"))),
+        }
+    }
+
+    ui.separator();
+
+    egui::Grid::new("orders_list").striped(true).show(ui, |ui| {
+        ui.strong("Code");
+        ui.strong("Kunde");
+        ui.strong("Gesamt");
+        ui.strong("");
+        ui.end_row();
+        for o in &run_orders {
+            ui.label(&o.order_code);
+            ui.label(&o.customer_name);
+            ui.label(eur(o.grand_total_cents));
+            if ui.button("Generate receipt").clicked() {
+                let Some(oid) = o.id else { continue };
+                match rt.block_on(invoices::build_for_order(db, oid)) {
+                    Ok(inv) => {
+                        let dir = state.receipt_export_dir.trim();
+                        let _ = std::fs::create_dir_all(dir);
+                        let path = format!("{dir}/{}.html", inv.invoice_number);
+                        match std::fs::write(&path, invoices::render_html(&inv)) {
+                            Ok(_) => state.receipt_msg = Some((true, format!("Rechnung gespeichert: {path}"))),
+                            Err(e) => state.receipt_msg = Some((false, format!("Schreiben fehlgeschlagen: {e}"))),
+                        }
+                    }
+                    Err(e) => state.receipt_msg = Some((false, format!("Rechnung fehlgeschlagen: {e}"))),
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/* ---------------- Stats ---------------- */
+
+fn page_stats(
+    ui: &mut egui::Ui,
+    rt: &tokio::runtime::Runtime,
+    db: &crate::db::Db,
+    repo: &dyn DishRepo,
+    state: &mut AdminState,
+) {
+    use crate::services::stats;
+
+    ui.heading("Stats");
+
+    let sups = rt.block_on(repo.list_suppliers()).unwrap_or_default();
+
+    ui.horizontal(|ui| {
+        ui.label("Zeitraum (Tage zurück)");
+        ui.add(egui::DragValue::new(&mut state.stats_days_back).range(1..=3650));
+    });
+
+    let to = mongodb::bson::DateTime::now();
+    let from = mongodb::bson::DateTime::from_millis(
+        to.timestamp_millis() - state.stats_days_back * 86_400_000,
+    );
+
+    ui.separator();
+    ui.heading("Umsatz pro Lieferant");
+    match rt.block_on(stats::supplier_totals(db, from, to)) {
+        Ok(totals) => {
+            if totals.is_empty() {
+                ui.label("Keine Bestellungen in diesem Zeitraum.");
+            } else {
+                egui::Grid::new("stats_supplier_totals").striped(true).show(ui, |ui| {
+                    ui.strong("Lieferant");
+                    ui.strong("Bestellungen");
+                    ui.strong("Umsatz");
+                    ui.end_row();
+                    for t in &totals {
+                        ui.label(id_to_name(&sups, t.supplier_id));
+                        ui.label(t.orders.to_string());
+                        ui.label(eur_cents(t.revenue_cents));
+                        ui.end_row();
+                    }
+                });
+            }
+        }
+        Err(e) => { ui.colored_label(state.theme.error_color(), format!("Stats fehlgeschlagen: {e}")); }
+    }
+
+    ui.separator();
+    ui.heading("Beliebteste Gerichte");
+    if sups.is_empty() {
+        ui.label("No suppliers yet.");
+        return;
+    }
+    if state.stats_supplier_idx >= sups.len() {
+        state.stats_supplier_idx = 0;
+    }
+    egui::ComboBox::from_label("Supplier")
+        .selected_text(sups[state.stats_supplier_idx].name.clone())
+        .show_ui(ui, |cb| {
+            for (i, s) in sups.iter().enumerate() {
+                cb.selectable_value(&mut state.stats_supplier_idx, i, s.name.clone());
+            }
+        });
+    let sid = sups[state.stats_supplier_idx].id.unwrap();
+    match rt.block_on(stats::dish_popularity(db, sid)) {
+        Ok(rows) => {
+            if rows.is_empty() {
+                ui.label("Keine Bestellungen für diesen Lieferanten.");
+            } else {
+                let max_count = rows.iter().map(|(_, c, _)| *c).max().unwrap_or(1).max(1);
+                egui::Grid::new("stats_dish_popularity").striped(true).show(ui, |ui| {
+                    ui.strong("Gericht");
+                    ui.strong("Bestellt");
+                    ui.strong("Umsatz");
+                    ui.end_row();
+                    for (dish, count, revenue_cents) in &rows {
+                        ui.label(&dish.name);
+                        ui.add(egui::ProgressBar::new(*count as f32 / max_count as f32).text(count.to_string()));
+                        ui.label(eur_cents(*revenue_cents));
+                        ui.end_row();
+                    }
+                });
+            }
+        }
+        Err(e) => { ui.colored_label(state.theme.error_color(), format!("Stats fehlgeschlagen: {e}")); }
+    }
+
+    ui.separator();
+    ui.heading("Umsatz über Zeit");
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut state.stats_bucket, stats::Bucket::Day, "Tag");
+        ui.selectable_value(&mut state.stats_bucket, stats::Bucket::Week, "Woche");
+    });
+    match rt.block_on(stats::spend_over_time(db, state.stats_bucket)) {
+        Ok(buckets) => {
+            if buckets.is_empty() {
+                ui.label("Keine Bestellungen vorhanden.");
+            } else {
+                let max_revenue = buckets.iter().map(|b| b.revenue_cents).max().unwrap_or(1).max(1);
+                egui::Grid::new("stats_spend_over_time").striped(true).show(ui, |ui| {
+                    for b in &buckets {
+                        ui.label(b.bucket_start.to_string());
+                        ui.add(egui::ProgressBar::new(b.revenue_cents as f32 / max_revenue as f32).text(eur_cents(b.revenue_cents)));
+                        ui.label(format!("{} Bestellungen", b.orders));
+                        ui.end_row();
+                    }
+                });
+            }
+        }
+        Err(e) => { ui.colored_label(state.theme.error_color(), format!("Stats fehlgeschlagen: {e}")); }
+    }
+}
+
+fn eur_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.abs();
+    format!("{sign}€{}.{:02}", abs / 100, abs % 100)
 }
 
 /* ---------------- Settings ---------------- */
@@ -631,10 +1197,99 @@ fn page_settings(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     state: &mut AdminState,
 ) {
     ui.heading("Settings");
-    let sups = rt.block_on(suppliers::list(db)).unwrap_or_default();
+
+    let can_edit = state.role.can_edit();
+
+    ui.horizontal(|ui| {
+        ui.label("Theme");
+        egui::ComboBox::from_label("")
+            .selected_text(state.theme.name())
+            .show_ui(ui, |cb| {
+                for t in Theme::ALL {
+                    cb.selectable_value(&mut state.theme, t, t.name());
+                }
+            });
+        if ui.add_enabled(can_edit, egui::Button::new("Save theme")).clicked() {
+            let _ = rt.block_on(settings::set_theme_name(db, state.theme.name()));
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Update channel");
+        egui::ComboBox::from_id_salt("update_channel")
+            .selected_text(state.update_channel.label())
+            .show_ui(ui, |cb| {
+                for c in crate::config::UpdateChannel::ALL {
+                    cb.selectable_value(&mut state.update_channel, c, c.label());
+                }
+            });
+        if ui.add_enabled(can_edit, egui::Button::new("Save channel")).clicked() {
+            if let Ok(mut cfg) = crate::config::load() {
+                cfg.update_channel = state.update_channel;
+                let _ = crate::config::save(&cfg);
+            }
+        }
+    });
+
+    ui.separator();
+    ui.heading("Benutzerverwaltung");
+    if state.role != Role::Owner {
+        ui.label("Nur für Owner sichtbar.");
+    } else {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut state.new_user_name);
+            ui.add(egui::TextEdit::singleline(&mut state.new_user_pass).password(true));
+            egui::ComboBox::from_label("Rolle")
+                .selected_text(Role::ALL[state.new_user_role_idx].label())
+                .show_ui(ui, |cb| {
+                    for (i, r) in Role::ALL.iter().enumerate() {
+                        cb.selectable_value(&mut state.new_user_role_idx, i, r.label());
+                    }
+                });
+            if ui.button("Benutzer anlegen").clicked() && !state.new_user_name.trim().is_empty() {
+                let role = Role::ALL[state.new_user_role_idx];
+                match rt.block_on(users::create_user(db, state.new_user_name.trim(), &state.new_user_pass, role)) {
+                    Ok(_) => {
+                        state.users_msg = Some((true, "Benutzer angelegt".to_string()));
+                        state.new_user_name.clear();
+                        state.new_user_pass.clear();
+                    }
+                    Err(e) => state.users_msg = Some((false, format!("Fehlgeschlagen: {e}"))),
+                }
+            }
+        });
+
+        if let Some((ok, msg)) = &state.users_msg {
+            let color = if *ok { egui::Color32::from_rgb(20, 160, 20) } else { state.theme.error_color() };
+            ui.colored_label(color, msg);
+        }
+
+        ui.label("Bestehende Benutzer");
+        let existing = rt.block_on(users::list(db)).unwrap_or_default();
+        for u in existing {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} ({})", u.username, u.role.label()));
+                if let Some(id) = u.id {
+                    for r in Role::ALL {
+                        if u.role != r && ui.button(format!("→ {}", r.label())).clicked() {
+                            let _ = rt.block_on(users::set_role(db, id, r));
+                        }
+                    }
+                    if ui.button("Löschen").clicked() {
+                        let _ = rt.block_on(users::delete(db, id));
+                    }
+                }
+            });
+        }
+    }
+
+    ui.separator();
+
+    let sups = rt.block_on(repo.list_suppliers()).unwrap_or_default();
     if sups.is_empty() { ui.label("No suppliers yet. Create one first."); return; }
     if state.set_supplier_idx >= sups.len() { state.set_supplier_idx = 0; }
 
@@ -652,11 +1307,32 @@ fn page_settings(
                 cb.selectable_value(&mut state.set_supplier_idx, i, s.name.clone());
             }
         });
-    if ui.button("Set active").clicked() {
+    if ui.add_enabled(can_edit, egui::Button::new("Set active")).clicked() {
         let sid = sups[state.set_supplier_idx].id.unwrap();
         let _ = rt.block_on(settings::set_active_supplier(db, sid));
     }
 
+    ui.separator();
+    ui.heading("Speisekarte exportieren (HTML)");
+    ui.horizontal(|ui| {
+        ui.label("Datei");
+        ui.text_edit_singleline(&mut state.menu_export_path);
+        if ui.add_enabled(can_edit, egui::Button::new("Export menu (HTML)")).clicked() {
+            let sid = sups[state.set_supplier_idx].id.unwrap();
+            match rt.block_on(crate::export::menu_html(db, sid)) {
+                Ok(html) => match std::fs::write(state.menu_export_path.trim(), html) {
+                    Ok(_) => state.menu_export_msg = Some((true, format!("Export erfolgreich: {}", state.menu_export_path.trim()))),
+                    Err(e) => state.menu_export_msg = Some((false, format!("Schreiben fehlgeschlagen: {e}"))),
+                },
+                Err(e) => state.menu_export_msg = Some((false, format!("Export fehlgeschlagen: {e}"))),
+            }
+        }
+    });
+    if let Some((ok, msg)) = &state.menu_export_msg {
+        let color = if *ok { egui::Color32::from_rgb(20,160,20) } else { state.theme.error_color() };
+        ui.colored_label(color, msg);
+    }
+
     ui.separator();
     ui.heading("Backup (verschlüsselt)");
 
@@ -668,7 +1344,7 @@ fn page_settings(
     ui.horizontal(|ui| {
         ui.label("Export-Datei");
         ui.text_edit_singleline(&mut state.backup_export_path);
-        if ui.button("Export (encrypted)").clicked() {
+        if ui.add_enabled(can_edit, egui::Button::new("Export (encrypted)")).clicked() {
             if state.backup_pass.is_empty() || state.backup_export_path.trim().is_empty() {
                 state.backup_msg = Some((false, "Bitte Passwort und Dateipfad ausfüllen.".into()));
             } else {
@@ -685,18 +1361,18 @@ fn page_settings(
     });
 
     ui.horizontal(|ui| {
-        ui.label("Import-Datei");
+        ui.label("Import-Datei oder URL");
         ui.text_edit_singleline(&mut state.backup_import_path);
-        if ui.button("Import (encrypted)").clicked() {
+        if ui.add_enabled(can_edit, egui::Button::new("Import (encrypted)")).clicked() {
             if state.backup_pass.is_empty() || state.backup_import_path.trim().is_empty() {
-                state.backup_msg = Some((false, "Bitte Passwort und Dateipfad ausfüllen.".into()));
+                state.backup_msg = Some((false, "Bitte Passwort und Dateipfad/URL ausfüllen.".into()));
             } else {
-                match rt.block_on(crate::services::backup::import_from_file(
+                match rt.block_on(crate::services::backup::import_from_source(
                     db,
                     state.backup_import_path.trim(),
                     state.backup_pass.trim(),
                 )) {
-                    Ok(_) => state.backup_msg = Some((true, "Import erfolgreich (DB ersetzt).".into())),
+                    Ok(snapshot_path) => state.backup_msg = Some((true, format!("Import erfolgreich (DB ersetzt). Sicherung des vorherigen Stands: {snapshot_path}"))),
                     Err(e) => state.backup_msg = Some((false, format!("Import fehlgeschlagen: {e}"))),
                 }
             }
@@ -704,10 +1380,344 @@ fn page_settings(
     });
 
     if let Some((ok, msg)) = &state.backup_msg {
-        let color = if *ok { egui::Color32::from_rgb(20,160,20) } else { egui::Color32::RED };
+        let color = if *ok { egui::Color32::from_rgb(20,160,20) } else { state.theme.error_color() };
         ui.colored_label(color, msg);
     }
 
+    ui.separator();
+    ui.heading("CSV-Export (Tabellenkalkulation)");
+
+    const CSV_COLLECTIONS: [(crate::services::backup::CsvCollection, &str); 3] = [
+        (crate::services::backup::CsvCollection::Suppliers, "Lieferanten"),
+        (crate::services::backup::CsvCollection::Orders, "Bestellungen"),
+        (crate::services::backup::CsvCollection::OrderItems, "Bestellpositionen"),
+    ];
+
+    ui.horizontal(|ui| {
+        ui.label("Tabelle");
+        egui::ComboBox::from_label("")
+            .selected_text(CSV_COLLECTIONS[state.csv_collection_idx].1)
+            .show_ui(ui, |cb| {
+                for (i, (_, label)) in CSV_COLLECTIONS.iter().enumerate() {
+                    cb.selectable_value(&mut state.csv_collection_idx, i, *label);
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Datei");
+        ui.text_edit_singleline(&mut state.csv_export_path);
+        if ui.add_enabled(can_edit, egui::Button::new("Export (CSV)")).clicked() {
+            let collection = CSV_COLLECTIONS[state.csv_collection_idx].0;
+            match rt.block_on(crate::services::backup::export_csv(db, state.csv_export_path.trim(), collection)) {
+                Ok(_) => state.backup_msg = Some((true, format!("CSV-Export erfolgreich: {}", state.csv_export_path.trim()))),
+                Err(e) => state.backup_msg = Some((false, format!("CSV-Export fehlgeschlagen: {e}"))),
+            }
+        }
+    });
+
+    ui.separator();
+    ui.heading("Streamed Backup (mit Fortschrittsanzeige)");
+
+    if let Some(rx) = state.backup_worker_rx.take() {
+        let mut still_running = true;
+        while let Ok(op) = rx.try_recv() {
+            match op {
+                crate::services::backup::Op::Progress { done, total } => {
+                    state.backup_progress = Some((done, total));
+                }
+                crate::services::backup::Op::Done => {
+                    state.backup_msg = Some((true, "Streamed-Vorgang erfolgreich.".into()));
+                    state.backup_progress = None;
+                    still_running = false;
+                }
+                crate::services::backup::Op::Err(e) => {
+                    state.backup_msg = Some((false, format!("Streamed-Vorgang fehlgeschlagen: {e}")));
+                    state.backup_progress = None;
+                    still_running = false;
+                }
+            }
+        }
+        if still_running {
+            state.backup_worker_rx = Some(rx);
+        }
+    }
+    if state.backup_worker_rx.is_some() {
+        ui.ctx().request_repaint();
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Export-Datei");
+        ui.text_edit_singleline(&mut state.streamed_export_path);
+        let busy = state.backup_worker_rx.is_some();
+        if ui.add_enabled(can_edit && !busy, egui::Button::new("Export (streamed)")).clicked() {
+            if state.backup_pass.is_empty() || state.streamed_export_path.trim().is_empty() {
+                state.backup_msg = Some((false, "Bitte Passwort und Dateipfad ausfüllen.".into()));
+            } else {
+                state.backup_progress = Some((0, 1));
+                state.backup_worker_rx = Some(crate::services::backup::export_streamed(
+                    rt.handle().clone(),
+                    db.clone(),
+                    state.streamed_export_path.trim().to_string(),
+                    state.backup_pass.trim().to_string(),
+                ));
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Import-Datei");
+        ui.text_edit_singleline(&mut state.streamed_import_path);
+        let busy = state.backup_worker_rx.is_some();
+        if ui.add_enabled(can_edit && !busy, egui::Button::new("Import (streamed)")).clicked() {
+            if state.backup_pass.is_empty() || state.streamed_import_path.trim().is_empty() {
+                state.backup_msg = Some((false, "Bitte Passwort und Dateipfad ausfüllen.".into()));
+            } else {
+                state.backup_progress = Some((0, 1));
+                state.backup_worker_rx = Some(crate::services::backup::import_streamed(
+                    rt.handle().clone(),
+                    db.clone(),
+                    state.streamed_import_path.trim().to_string(),
+                    state.backup_pass.trim().to_string(),
+                ));
+            }
+        }
+    });
+    if let Some((done, total)) = state.backup_progress {
+        ui.add(egui::ProgressBar::new(done as f32 / total.max(1) as f32).text(format!("{done}/{total}")));
+    }
+
+    ui.separator();
+    ui.heading("Selektives Restore");
+    ui.horizontal(|ui| {
+        if ui.add_enabled(can_edit, egui::Button::new("Inhalt anzeigen")).clicked() {
+            match crate::services::backup::list_contents(state.backup_import_path.trim(), state.backup_pass.trim()) {
+                Ok(entries) => {
+                    state.catalog_selection.clear();
+                    state.catalog = entries;
+                }
+                Err(e) => state.backup_msg = Some((false, format!("Katalog fehlgeschlagen: {e}"))),
+            }
+        }
+        ui.checkbox(&mut state.catalog_merge, "Merge statt Replace");
+    });
+    for entry in &state.catalog {
+        let mut checked = state.catalog_selection.contains(&entry.name);
+        if ui.checkbox(&mut checked, format!("{} ({} Dokumente, {} Bytes)", entry.name, entry.doc_count, entry.byte_size)).clicked() {
+            if checked {
+                state.catalog_selection.insert(entry.name.clone());
+            } else {
+                state.catalog_selection.remove(&entry.name);
+            }
+        }
+    }
+    if !state.catalog.is_empty() && ui.add_enabled(can_edit, egui::Button::new("Ausgewählte Collections wiederherstellen")).clicked() {
+        let mode = if state.catalog_merge {
+            crate::services::backup::ImportMode::Merge
+        } else {
+            crate::services::backup::ImportMode::Replace
+        };
+        match rt.block_on(crate::services::backup::import_selective(
+            db,
+            state.backup_import_path.trim(),
+            state.backup_pass.trim(),
+            &state.catalog_selection,
+            mode,
+        )) {
+            Ok(_) => state.backup_msg = Some((true, "Selektives Restore erfolgreich.".into())),
+            Err(e) => state.backup_msg = Some((false, format!("Restore fehlgeschlagen: {e}"))),
+        }
+    }
+
+    ui.separator();
+    ui.heading("Remote-Ziel (S3-kompatibel)");
+    ui.horizontal(|ui| {
+        ui.label("Endpoint");
+        ui.text_edit_singleline(&mut state.s3_endpoint);
+        ui.label("Region");
+        ui.text_edit_singleline(&mut state.s3_region);
+        ui.label("Bucket");
+        ui.text_edit_singleline(&mut state.s3_bucket);
+    });
+    ui.horizontal(|ui| {
+        ui.label("Access Key");
+        ui.text_edit_singleline(&mut state.s3_access_key);
+        ui.label("Secret Key");
+        ui.add(egui::TextEdit::singleline(&mut state.s3_secret_key).password(true));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Objekt-Schlüssel");
+        ui.text_edit_singleline(&mut state.s3_key);
+    });
+
+    let s3_target = || crate::services::backup_target::BackupTarget::S3(crate::model::S3BackupConfig {
+        endpoint: state.s3_endpoint.trim().to_string(),
+        region: state.s3_region.trim().to_string(),
+        bucket: state.s3_bucket.trim().to_string(),
+        access_key: state.s3_access_key.trim().to_string(),
+        secret_key: state.s3_secret_key.trim().to_string(),
+    });
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(can_edit, egui::Button::new("Export nach S3")).clicked() {
+            let target = s3_target();
+            match rt.block_on(crate::services::backup::export_to_target(db, &target, state.s3_key.trim(), state.backup_pass.trim())) {
+                Ok(_) => state.backup_msg = Some((true, "Export nach S3 erfolgreich.".into())),
+                Err(e) => state.backup_msg = Some((false, format!("S3-Export fehlgeschlagen: {e}"))),
+            }
+        }
+        if ui.add_enabled(can_edit, egui::Button::new("Import von S3")).clicked() {
+            let target = s3_target();
+            match rt.block_on(crate::services::backup::import_from_target(db, &target, state.s3_key.trim(), state.backup_pass.trim())) {
+                Ok(_) => state.backup_msg = Some((true, "Import von S3 erfolgreich.".into())),
+                Err(e) => state.backup_msg = Some((false, format!("S3-Import fehlgeschlagen: {e}"))),
+            }
+        }
+        if ui.add_enabled(can_edit, egui::Button::new("Katalog von S3 anzeigen")).clicked() {
+            let target = s3_target();
+            match rt.block_on(crate::services::backup::list_contents_from_target(&target, state.s3_key.trim(), state.backup_pass.trim())) {
+                Ok(entries) => {
+                    state.catalog_selection.clear();
+                    state.catalog = entries;
+                }
+                Err(e) => state.backup_msg = Some((false, format!("S3-Katalog fehlgeschlagen: {e}"))),
+            }
+        }
+        if ui.add_enabled(can_edit, egui::Button::new("Als Standard-Ziel speichern")).clicked() {
+            let cfg = crate::model::S3BackupConfig {
+                endpoint: state.s3_endpoint.trim().to_string(),
+                region: state.s3_region.trim().to_string(),
+                bucket: state.s3_bucket.trim().to_string(),
+                access_key: state.s3_access_key.trim().to_string(),
+                secret_key: state.s3_secret_key.trim().to_string(),
+            };
+            let _ = rt.block_on(crate::services::settings::set_s3_backup(db, Some(cfg)));
+        }
+    });
+
+    ui.separator();
+    ui.heading("Keyfile (Master-Key statt Passwort)");
+    ui.horizontal(|ui| {
+        ui.label("Keyfile-Pfad");
+        ui.text_edit_singleline(&mut state.keyfile_path);
+        if ui.add_enabled(can_edit, egui::Button::new("Keyfile erzeugen")).clicked() {
+            match crate::services::backup::generate_keyfile(state.keyfile_path.trim(), state.backup_pass.trim()) {
+                Ok(fp) => {
+                    state.keyfile_fingerprint = Some(fp.clone());
+                    state.backup_msg = Some((true, format!("Keyfile erzeugt, Fingerprint: {fp}")));
+                }
+                Err(e) => state.backup_msg = Some((false, format!("Keyfile-Erzeugung fehlgeschlagen: {e}"))),
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui.add_enabled(can_edit, egui::Button::new("Mit Keyfile exportieren")).clicked() {
+            match crate::services::backup::unlock_keyfile(state.keyfile_path.trim(), state.backup_pass.trim())
+                .and_then(|(key, fp)| {
+                    state.keyfile_fingerprint = Some(fp.clone());
+                    rt.block_on(crate::services::backup::export_to_file_keyed(
+                        db,
+                        state.backup_export_path.trim(),
+                        &key,
+                        &fp,
+                    ))
+                }) {
+                Ok(_) => state.backup_msg = Some((true, "Export mit Keyfile erfolgreich.".into())),
+                Err(e) => state.backup_msg = Some((false, format!("Export fehlgeschlagen: {e}"))),
+            }
+        }
+        if ui.add_enabled(can_edit, egui::Button::new("Mit Keyfile importieren")).clicked() {
+            match crate::services::backup::unlock_keyfile(state.keyfile_path.trim(), state.backup_pass.trim())
+                .and_then(|(key, fp)| {
+                    state.keyfile_fingerprint = Some(fp.clone());
+                    rt.block_on(crate::services::backup::import_from_file_keyed(
+                        db,
+                        state.backup_import_path.trim(),
+                        &key,
+                        &fp,
+                    ))
+                }) {
+                Ok(_) => state.backup_msg = Some((true, "Import mit Keyfile erfolgreich.".into())),
+                Err(e) => state.backup_msg = Some((false, format!("Import fehlgeschlagen: {e}"))),
+            }
+        }
+        if let Some(fp) = &state.keyfile_fingerprint {
+            ui.monospace(format!("Fingerprint: {fp}"));
+        }
+    });
+
+    ui.separator();
+    ui.heading("Backup (inkrementell, dedupliziert)");
+
+    ui.horizontal(|ui| {
+        ui.label("Backup-Verzeichnis");
+        ui.text_edit_singleline(&mut state.backup_incremental_dir);
+        if ui.add_enabled(can_edit, egui::Button::new("Export (incremental)")).clicked() {
+            if state.backup_pass.is_empty() || state.backup_incremental_dir.trim().is_empty() {
+                state.backup_msg = Some((false, "Bitte Passwort und Verzeichnis ausfüllen.".into()));
+            } else {
+                match rt.block_on(crate::services::backup::export_incremental(
+                    db,
+                    state.backup_incremental_dir.trim(),
+                    state.backup_pass.trim(),
+                )) {
+                    Ok(path) => state.backup_msg = Some((true, format!("Export erfolgreich: {}", path.display()))),
+                    Err(e) => state.backup_msg = Some((false, format!("Export fehlgeschlagen: {e}"))),
+                }
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Index-Datei (.bdkidx)");
+        ui.text_edit_singleline(&mut state.backup_incremental_index_path);
+        if ui.add_enabled(can_edit, egui::Button::new("Import (incremental)")).clicked() {
+            if state.backup_pass.is_empty() || state.backup_incremental_index_path.trim().is_empty() {
+                state.backup_msg = Some((false, "Bitte Passwort und Index-Datei ausfüllen.".into()));
+            } else {
+                match rt.block_on(crate::services::backup::import_incremental(
+                    db,
+                    state.backup_incremental_index_path.trim(),
+                    state.backup_pass.trim(),
+                )) {
+                    Ok(_) => state.backup_msg = Some((true, "Import erfolgreich (DB ersetzt).".into())),
+                    Err(e) => state.backup_msg = Some((false, format!("Import fehlgeschlagen: {e}"))),
+                }
+            }
+        }
+    });
+
+    ui.separator();
+    ui.label("Aufbewahrung (prune)");
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut state.prune_keep_last).range(0..=100).prefix("last: "));
+        ui.add(egui::DragValue::new(&mut state.prune_keep_daily).range(0..=365).prefix("daily: "));
+        ui.add(egui::DragValue::new(&mut state.prune_keep_weekly).range(0..=260).prefix("weekly: "));
+        ui.add(egui::DragValue::new(&mut state.prune_keep_monthly).range(0..=120).prefix("monthly: "));
+        ui.add(egui::DragValue::new(&mut state.prune_keep_yearly).range(0..=50).prefix("yearly: "));
+    });
+    ui.horizontal(|ui| {
+        let spec = crate::services::backup::RetentionSpec {
+            keep_last: state.prune_keep_last,
+            keep_daily: state.prune_keep_daily,
+            keep_weekly: state.prune_keep_weekly,
+            keep_monthly: state.prune_keep_monthly,
+            keep_yearly: state.prune_keep_yearly,
+        };
+        if ui.add_enabled(can_edit, egui::Button::new("Preview prune (dry-run)")).clicked() {
+            match crate::services::backup::prune(state.backup_incremental_dir.trim(), state.backup_pass.trim(), &spec, true) {
+                Ok(r) => state.backup_msg = Some((true, format!("Würde behalten: {}, löschen: {}", r.kept.len(), r.removed.len()))),
+                Err(e) => state.backup_msg = Some((false, format!("Prune fehlgeschlagen: {e}"))),
+            }
+        }
+        if ui.add_enabled(can_edit, egui::Button::new("Prune now")).clicked() {
+            match crate::services::backup::prune(state.backup_incremental_dir.trim(), state.backup_pass.trim(), &spec, false) {
+                Ok(r) => {
+                    let _ = crate::services::backup::gc_chunks(state.backup_incremental_dir.trim(), state.backup_pass.trim());
+                    state.backup_msg = Some((true, format!("Behalten: {}, gelöscht: {}", r.kept.len(), r.removed.len())));
+                }
+                Err(e) => state.backup_msg = Some((false, format!("Prune fehlgeschlagen: {e}"))),
+            }
+        }
+    });
 }
 
 fn id_to_name(sups: &[Supplier], id: ObjectId) -> String {
@@ -1,7 +1,10 @@
 use eframe::egui;
 
+use crate::services::dish_repo::DishRepo;
+
 pub mod order;
 pub mod admin;
+pub mod theme;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UiTab { Order, Admin }
@@ -11,19 +14,22 @@ pub fn render_order(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     state: &mut order::OrderState,
 ) {
-    order::render(ui, rt, db, state);
+    order::render(ui, rt, db, repo, state);
 }
 
 pub fn render_admin(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     user: &mut String,
     pass: &mut String,
     authed: &mut bool,
+    role: &mut Option<crate::model::Role>,
     state: &mut admin::AdminState,
 ) {
-    admin::render(ui, rt, db, user, pass, authed, state);
+    admin::render(ui, rt, db, repo, user, pass, authed, role, state);
 }
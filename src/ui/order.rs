@@ -1,34 +1,98 @@
 // src/ui/order.rs
+use std::time::Duration;
+
+use anyhow::Context;
 use eframe::egui;
 use mongodb::bson::oid::ObjectId;
 
-use crate::model::{Category, Dish};
-use crate::services::{categories, dishes, orders, settings, suppliers};
+use crate::cache::Cached;
+use crate::model::{Category, Customer, Dish, VariantOption};
+use crate::services::dish_repo::DishRepo;
+use crate::services::{categories, customers, orders, settings};
+
+/// How long a loaded menu stays fresh before the order screen re-fetches it
+/// — long enough to avoid a DB round-trip on every frame, short enough that
+/// edits made in the admin panel show up without rebuilding the screen.
+const MENU_TTL: Duration = Duration::from_secs(60);
+
+/// How long the "previously used names" dropdown stays fresh — shared kiosks
+/// don't get new customers often enough to need a tighter refresh than this.
+const KNOWN_CUSTOMERS_TTL: Duration = Duration::from_secs(30);
+
+/// The active supplier's menu, fetched together and cached as one unit.
+struct MenuData {
+    supplier_id: ObjectId,
+    supplier_name: String,
+    delivery_fee_cents: i64,
+    dishes: Vec<Dish>,
+    categories: Vec<Category>,
+}
+
+async fn fetch_menu(db: &crate::db::Db, repo: &dyn DishRepo) -> anyhow::Result<MenuData> {
+    let sid = settings::get_active_supplier_id(db)
+        .await?
+        .context("No active supplier in settings")?;
+    let supp = repo.get_supplier(sid).await?.context("Active supplier not found")?;
+    let mut dishes = repo.list_by_supplier(sid).await?;
+    dishes.sort_by_key(dish_sort_key);
+    let categories = categories::list_by_supplier(db, sid).await?;
+    Ok(MenuData {
+        supplier_id: sid,
+        supplier_name: supp.name,
+        delivery_fee_cents: supp.delivery_fee_cents,
+        dishes,
+        categories,
+    })
+}
 
 #[derive(Clone)]
 pub(crate) struct ItemSel {
-    pub(crate) dish_idx: usize,         // Index in state.dishes (global)
+    // Identifies the dish by id rather than a position in `menu.dishes`: the
+    // menu is a `Cached<MenuData>` that can be silently refetched between
+    // frames (admin-side edits invalidate it), and a refetched `dishes` vec
+    // has no guaranteed length or order, so a stored index could point at
+    // the wrong dish or be out of bounds.
+    pub(crate) dish_id: ObjectId,
     pub(crate) qty: i32,
-    pub(crate) size_idx: Option<usize>, // nur für Pizza
-    pub(crate) note: String,            // optional
+    pub(crate) size_idx: Option<usize>,  // selected option within the dish's first variant group (if any)
+    pub(crate) note: String,             // optional
+}
+
+/// Returns the dish's variant options to choose from, preferring the generic
+/// `variant_groups` system and falling back to the legacy `pizza_sizes`
+/// field for dishes created before it existed. Only the first group is
+/// orderable here; extras and additional groups aren't priced yet.
+fn primary_options(d: &Dish) -> Option<Vec<VariantOption>> {
+    if let Some(groups) = d.variant_groups.as_ref().filter(|g| !g.is_empty()) {
+        return Some(groups[0].options.clone());
+    }
+    d.pizza_sizes.as_ref().filter(|s| !s.is_empty()).map(|sizes| {
+        sizes.iter().map(|s| VariantOption { label: s.label.clone(), price_cents: s.price_cents }).collect()
+    })
 }
 
 #[derive(Default)]
 pub struct OrderState {
-    pub supplier_name: String,
-    pub delivery_fee_cents: i64,
-    pub supplier_id: Option<ObjectId>,
+    menu: Cached<MenuData>,
+    known_customers: Cached<Vec<Customer>>,
 
-    pub dishes: Vec<Dish>,
-    pub categories: Vec<Category>,
     pub active_category: Option<ObjectId>, // None = All
 
     pub(crate) selections: Vec<ItemSel>,
     pub customer_name: String,
+    pub customer_note: String,
+    /// Set once the saved profile for `client_id` has been loaded, so we
+    /// don't keep overwriting text the user already started typing.
+    customer_loaded: bool,
     pub client_id: String,
+}
 
-    pub load_err: Option<String>,
-    pub loaded: bool,
+impl OrderState {
+    /// Forces the next `render` call to re-fetch the menu, e.g. after a
+    /// watcher reports the suppliers/dishes/settings collections changed.
+    pub(crate) fn invalidate_menu(&mut self) {
+        self.menu.invalidate();
+    }
 }
 
 /* ---------- helpers ---------- */
@@ -56,7 +120,7 @@ fn dish_label(d: &Dish) -> String {
     } else {
         format!("Nr. {}: {}", nr, d.name)
     };
-    if d.tags.iter().any(|t| t == "Pizza") {
+    if primary_options(d).is_some() {
         base
     } else {
         format!("{} ({})", base, eur(d.price_cents))
@@ -64,10 +128,10 @@ fn dish_label(d: &Dish) -> String {
 }
 
 // Indizes der Gerichte, die zur aktiven Kategorie passen (oder alle)
-fn filtered_indices(state: &OrderState) -> Vec<usize> {
-    match state.active_category {
-        None => (0..state.dishes.len()).collect(),
-        Some(cid) => state
+fn filtered_indices(menu: &MenuData, active_category: Option<ObjectId>) -> Vec<usize> {
+    match active_category {
+        None => (0..menu.dishes.len()).collect(),
+        Some(cid) => menu
             .dishes
             .iter()
             .enumerate()
@@ -77,100 +141,98 @@ fn filtered_indices(state: &OrderState) -> Vec<usize> {
     }
 }
 
+/// Looks up a dish by id in the current menu — used instead of a stored
+/// index so a selection survives the menu being refetched out from under it.
+fn resolve_dish<'a>(menu: &'a MenuData, dish_id: ObjectId) -> Option<&'a Dish> {
+    menu.dishes.iter().find(|d| d.id == Some(dish_id))
+}
+
 /* ---------- UI ---------- */
 
 pub fn render(
     ui: &mut egui::Ui,
     rt: &tokio::runtime::Runtime,
     db: &crate::db::Db,
+    repo: &dyn DishRepo,
     state: &mut OrderState,
 ) {
-    // Initial laden
-    if !state.loaded && state.load_err.is_none() {
-        let res = rt.block_on(async {
-            if let Some(sid) = settings::get_active_supplier_id(db).await? {
-                if let Some(supp) = suppliers::get(db, sid).await? {
-                    let mut ds = dishes::list_by_supplier(db, sid).await?;
-                    // sortieren
-                    ds.sort_by_key(dish_sort_key);
-
-                    let cats = categories::list_by_supplier(db, sid).await?;
-
-                    Ok::<_, anyhow::Error>((
-                        Some(sid),
-                        supp.name,
-                        supp.delivery_fee_cents,
-                        ds,
-                        cats,
-                    ))
-                } else {
-                    anyhow::bail!("Active supplier not found");
-                }
-            } else {
-                anyhow::bail!("No active supplier in settings");
-            }
-        });
+    ui.heading("Place your order");
 
-        match res {
-            Ok((sid, name, fee, ds, cats)) => {
-                state.supplier_id = sid;
-                state.supplier_name = name;
-                state.delivery_fee_cents = fee;
-                state.dishes = ds;
-                state.categories = cats;
-                // Default: All
-                state.active_category = None;
-
-                if state.selections.is_empty() {
-                    state.selections.push(ItemSel {
-                        dish_idx: 0,
-                        qty: 1,
-                        size_idx: None,
-                        note: String::new(),
-                    });
-                }
-                state.loaded = true;
-            }
-            Err(e) => state.load_err = Some(e.to_string()),
+    // Cached for MENU_TTL, so admin-side menu edits show up without
+    // rebuilding the screen but we don't hit the DB on every frame.
+    let menu = match state.menu.get_or_fetch(MENU_TTL, || rt.block_on(fetch_menu(db, repo))) {
+        Ok(m) => m,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, e.to_string());
+            ui.label("Admin must set an active supplier and menu.");
+            return;
         }
-    }
-
-    ui.heading("Place your order");
+    };
 
-    if let Some(err) = &state.load_err {
-        ui.colored_label(egui::Color32::RED, err);
-        ui.label("Admin must set an active supplier and menu.");
+    if menu.dishes.is_empty() {
+        ui.label("No dishes available.");
         return;
     }
-    if !state.loaded {
-        ui.label("Loading…");
-        return;
+
+    if state.selections.is_empty() {
+        state.selections.push(ItemSel {
+            dish_id: menu.dishes[0].id.expect("persisted dishes always have an id"),
+            qty: 1,
+            size_idx: None,
+            note: state.customer_note.clone(),
+        });
     }
-    if state.dishes.is_empty() {
-        ui.label("No dishes available.");
-        return;
+
+    if !state.customer_loaded {
+        if let Ok(Some(c)) = rt.block_on(customers::get_by_client(db, &state.client_id)) {
+            state.customer_name = c.display_name;
+            state.customer_note = c.note.unwrap_or_default();
+        }
+        state.customer_loaded = true;
     }
 
-    ui.label(format!("Supplier: {}", state.supplier_name));
-    ui.label(format!("Delivery fee: {}", eur(state.delivery_fee_cents)));
+    ui.label(format!("Supplier: {}", menu.supplier_name));
+    ui.label(format!("Delivery fee: {}", eur(menu.delivery_fee_cents)));
 
     ui.separator();
     ui.label("Your name");
     ui.text_edit_singleline(&mut state.customer_name);
 
+    if let Ok(known) = state
+        .known_customers
+        .get_or_fetch(KNOWN_CUSTOMERS_TTL, || rt.block_on(customers::list(db)))
+    {
+        if !known.is_empty() {
+            egui::ComboBox::from_id_salt("known_customers")
+                .selected_text("Previously used names")
+                .show_ui(ui, |cb| {
+                    for c in known.iter() {
+                        if cb.selectable_label(false, c.display_name.clone()).clicked() {
+                            state.customer_name = c.display_name.clone();
+                            state.customer_note = c.note.clone().unwrap_or_default();
+                        }
+                    }
+                });
+        }
+    }
+
+    ui.label("Contact / room / desk");
+    ui.text_edit_singleline(&mut state.customer_note);
+
     ui.separator();
     ui.horizontal(|ui| {
         // + / − Buttons
         if ui.button("+ Add dish").clicked() {
             // Voreinstellung: erste passende Option der aktiven Kategorie (falls vorhanden)
-            let f = filtered_indices(state);
-            let fallback = *f.get(0).unwrap_or(&0);
-            let last_idx = state.selections.last().map(|s| s.dish_idx).unwrap_or(fallback);
+            let f = filtered_indices(menu, state.active_category);
+            let fallback = menu.dishes[*f.get(0).unwrap_or(&0)].id.expect("persisted dishes always have an id");
+            let last_id = state.selections.last().map(|s| s.dish_id);
+            let dish_id = last_id.filter(|id| resolve_dish(menu, *id).is_some()).unwrap_or(fallback);
             state.selections.push(ItemSel {
-                dish_idx: last_idx,
+                dish_id,
                 qty: 1,
                 size_idx: None,
-                note: String::new(),
+                note: state.customer_note.clone(),
             });
         }
         if ui.button("− Remove last").clicked() && state.selections.len() > 1 {
@@ -186,20 +248,29 @@ pub fn render(
         // "All" Tab
         ui.selectable_value(&mut state.active_category, None, "All");
         // supplier-spezifische Kategorien
-        for c in &state.categories {
+        for c in &menu.categories {
             if let Some(cid) = c.id {
                 ui.selectable_value(&mut state.active_category, Some(cid), c.name.clone());
             }
         }
     });
 
-    let filtered = filtered_indices(state);
+    let filtered = filtered_indices(menu, state.active_category);
     if filtered.is_empty() {
         ui.label("No dishes in this category.");
     }
 
     // Selektionen rendern
     for (i, sel) in state.selections.iter_mut().enumerate() {
+        // The menu may have been refetched since this selection was made
+        // (e.g. an admin-side edit invalidated the cache); if the selected
+        // dish no longer exists, fall back to the first available one
+        // instead of indexing into a vec that may have changed shape.
+        if resolve_dish(menu, sel.dish_id).is_none() {
+            sel.dish_id = menu.dishes[0].id.expect("persisted dishes always have an id");
+            sel.size_idx = None;
+        }
+
         ui.push_id(i, |ui| {
             ui.group(|ui| {
                 ui.horizontal(|ui| {
@@ -207,20 +278,23 @@ pub fn render(
                     ui.label(format!("Dish #{}", i + 1));
 
                     // Aktueller Text für ausgewähltes Gericht
-                    let current_label = dish_label(&state.dishes[sel.dish_idx]);
+                    let current_label = dish_label(resolve_dish(menu, sel.dish_id).unwrap_or(&menu.dishes[0]));
 
                     egui::ComboBox::from_id_salt(("dish_select", i))
                         .selected_text(current_label)
                         .show_ui(ui, |cb| {
                             for idx in &filtered {
-                                let d = &state.dishes[*idx];
-                                cb.selectable_value(&mut sel.dish_idx, *idx, dish_label(d));
+                                let d = &menu.dishes[*idx];
+                                if let Some(id) = d.id {
+                                    cb.selectable_value(&mut sel.dish_id, id, dish_label(d));
+                                }
                             }
                         });
 
-                    // Größe (nur Pizza)
-                    let d = &state.dishes[sel.dish_idx];
-                    if let Some(sizes) = &d.pizza_sizes {
+                    // Variant-Auswahl (z.B. Größe), falls das Gericht welche hat
+                    let d = resolve_dish(menu, sel.dish_id).unwrap_or(&menu.dishes[0]);
+                    if let Some(sizes) = primary_options(d) {
+                        let sizes = &sizes;
                         if sel.size_idx.is_none() && !sizes.is_empty() {
                             sel.size_idx = Some(0);
                         }
@@ -258,8 +332,8 @@ pub fn render(
                 });
 
                 // Zeilensumme
-                let d = &state.dishes[sel.dish_idx];
-                let unit = if let Some(sizes) = &d.pizza_sizes {
+                let d = resolve_dish(menu, sel.dish_id).unwrap_or(&menu.dishes[0]);
+                let unit = if let Some(sizes) = primary_options(d) {
                     let idx = sel.size_idx.unwrap_or(0).min(sizes.len().saturating_sub(1));
                     sizes[idx].price_cents
                 } else {
@@ -276,8 +350,8 @@ pub fn render(
         .selections
         .iter()
         .map(|s| {
-            let d = &state.dishes[s.dish_idx];
-            let unit = if let Some(sizes) = &d.pizza_sizes {
+            let d = resolve_dish(menu, s.dish_id).unwrap_or(&menu.dishes[0]);
+            let unit = if let Some(sizes) = primary_options(d) {
                 let idx = s.size_idx.unwrap_or(0).min(sizes.len().saturating_sub(1));
                 sizes[idx].price_cents
             } else {
@@ -287,27 +361,26 @@ pub fn render(
         })
         .sum();
 
-    let grand_total = items_total + state.delivery_fee_cents;
+    let grand_total = items_total + menu.delivery_fee_cents;
 
     ui.separator();
     ui.label("Summary");
     ui.monospace(format!("Items total: {}", eur(items_total)));
-    ui.monospace(format!("Delivery fee: {}", eur(state.delivery_fee_cents)));
+    ui.monospace(format!("Delivery fee: {}", eur(menu.delivery_fee_cents)));
     ui.monospace(format!("Grand total: {}", eur(grand_total)));
 
-    let can_submit = state.supplier_id.is_some()
-        && !state.customer_name.trim().is_empty()
-        && !state.selections.is_empty();
+    let can_submit = !state.customer_name.trim().is_empty() && !state.selections.is_empty();
 
     if ui.add_enabled(can_submit, egui::Button::new("Submit order")).clicked() {
-        if let Some(supplier_id) = state.supplier_id {
+        {
+            let supplier_id = menu.supplier_id;
             // Items mit Namen/Nummer/Größe/Notiz aufbereiten
             let items: Vec<(ObjectId, String, i32, i64, Option<String>, Option<String>)> = state
                 .selections
                 .iter()
                 .map(|s| {
-                    let d = &state.dishes[s.dish_idx];
-                    if let Some(sizes) = &d.pizza_sizes {
+                    let d = resolve_dish(menu, s.dish_id).unwrap_or(&menu.dishes[0]);
+                    if let Some(sizes) = primary_options(d) {
                         let idx = s.size_idx.unwrap_or(0).min(sizes.len().saturating_sub(1));
                         let sz = &sizes[idx];
                         let nr = d.number.clone().unwrap_or_default();
@@ -349,18 +422,25 @@ pub fn render(
                 &state.customer_name,
                 supplier_id,
                 items,
-                state.delivery_fee_cents,
+                menu.delivery_fee_cents,
                 &state.client_id,
             ));
 
             match res {
                 Ok(_) => {
+                    let note = if state.customer_note.trim().is_empty() {
+                        None
+                    } else {
+                        Some(state.customer_note.trim())
+                    };
+                    let _ = rt.block_on(customers::upsert(db, &state.client_id, &state.customer_name, note));
+
                     state.selections.clear();
                     state.selections.push(ItemSel {
-                        dish_idx: 0,
+                        dish_id: menu.dishes[0].id.expect("persisted dishes always have an id"),
                         qty: 1,
                         size_idx: None,
-                        note: String::new(),
+                        note: state.customer_note.clone(),
                     });
                 }
                 Err(e) => {
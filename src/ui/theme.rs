@@ -0,0 +1,99 @@
+// src/ui/theme.rs
+//
+// Small, self-contained color palette applied on top of egui's default
+// visuals. Kept independent of egui::Visuals so it can be persisted as
+// plain data (just the preset name) in `settings`.
+
+use eframe::egui;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::Dark
+    }
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::HighContrast => "High Contrast",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Theme::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    pub fn background_color(&self) -> egui::Color32 {
+        match self {
+            Theme::Light => egui::Color32::from_rgb(245, 245, 245),
+            Theme::Dark => egui::Color32::from_rgb(27, 27, 27),
+            Theme::HighContrast => egui::Color32::BLACK,
+        }
+    }
+
+    pub fn panel_color(&self) -> egui::Color32 {
+        match self {
+            Theme::Light => egui::Color32::from_rgb(255, 255, 255),
+            Theme::Dark => egui::Color32::from_rgb(36, 36, 36),
+            Theme::HighContrast => egui::Color32::from_rgb(10, 10, 10),
+        }
+    }
+
+    pub fn text_color(&self) -> egui::Color32 {
+        match self {
+            Theme::Light => egui::Color32::from_rgb(20, 20, 20),
+            Theme::Dark => egui::Color32::from_rgb(230, 230, 230),
+            Theme::HighContrast => egui::Color32::WHITE,
+        }
+    }
+
+    pub fn accent_color(&self) -> egui::Color32 {
+        match self {
+            Theme::Light => egui::Color32::from_rgb(30, 110, 200),
+            Theme::Dark => egui::Color32::from_rgb(90, 160, 255),
+            Theme::HighContrast => egui::Color32::YELLOW,
+        }
+    }
+
+    pub fn error_color(&self) -> egui::Color32 {
+        match self {
+            Theme::Light => egui::Color32::from_rgb(200, 30, 30),
+            Theme::Dark => egui::Color32::from_rgb(255, 90, 90),
+            Theme::HighContrast => egui::Color32::RED,
+        }
+    }
+
+    pub fn button_color(&self) -> egui::Color32 {
+        match self {
+            Theme::Light => egui::Color32::from_rgb(225, 225, 225),
+            Theme::Dark => egui::Color32::from_rgb(55, 55, 55),
+            Theme::HighContrast => egui::Color32::from_rgb(40, 40, 40),
+        }
+    }
+
+    /// Builds an `egui::Visuals` reflecting this palette, applied via `ctx.set_visuals`.
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = match self {
+            Theme::Light => egui::Visuals::light(),
+            Theme::Dark | Theme::HighContrast => egui::Visuals::dark(),
+        };
+        visuals.override_text_color = Some(self.text_color());
+        visuals.panel_fill = self.panel_color();
+        visuals.window_fill = self.background_color();
+        visuals.widgets.inactive.bg_fill = self.button_color();
+        visuals.widgets.hovered.bg_fill = self.accent_color();
+        visuals.selection.bg_fill = self.accent_color();
+        visuals
+    }
+}